@@ -1,5 +1,6 @@
 //! The `data` module holds generic data structures for processing imported data.
 use crate::prelude::*;
+use crate::utils;
 use aid::prelude::*;
 use derive_more::{Deref, DerefMut};
 use indicatif::ProgressBar;
@@ -331,6 +332,17 @@ impl Business {
     }
 }
 
+impl crate::geocode::Geocodable for Business {
+    /// Geocodes the street address label, identified by the license number.
+    fn geocode_id(&self) -> String {
+        self.license.clone()
+    }
+
+    fn geocode_address(&self) -> String {
+        self.street_address_label.clone()
+    }
+}
+
 /// The `Businesses` struct contains a `records` field holding a vector of type [`Business`].
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, PartialOrd, Deref, DerefMut)]
 pub struct Businesses(Vec<Business>);
@@ -638,23 +650,32 @@ pub struct MailingListItem {
     mailing_csz: FlatList,
     associated_names: FlatList,
     parcels: FlatList,
+    parcel_situs: FlatList,
 }
 
 impl MailingListItem {
-    /// Creates a new `MailingListItem` from an [`crate::import::CityTaxlots`] struct.
-    pub fn from_city_parcels(
+    /// The `properties` field is the number of situs addresses associated with the owner.  This
+    /// method returns the value of the field.
+    pub fn properties(&self) -> usize {
+        self.properties
+    }
+
+    /// Creates a new `MailingListItem` for the owner `name` from any collection of taxlots
+    /// implementing [`Taxlots`].  Names already added to the mailing list are recorded in `done`,
+    /// including other owners that share the mailing address of `name`.
+    pub fn from_parcels<U: Taxlots>(
         name: &str,
-        parcels: &CityTaxlots,
+        parcels: &U,
         done: &mut std::collections::HashSet<String>,
     ) -> Clean<Self> {
         done.insert(name.to_string());
         let addr = parcels.associated_addresses(name);
         if !addr.is_empty() {
             let mut csz = parcels
+                .records()
                 .iter()
                 .filter(|v| addr.contains(v.address()))
-                .map(|v| v.csz())
-                .cloned()
+                .map(|v| v.csz().clone())
                 .collect::<Vec<String>>();
             csz.sort();
             csz.dedup();
@@ -671,17 +692,17 @@ impl MailingListItem {
                 .map(|v| v.to_string())
                 .collect::<Vec<String>>();
             let situs = parcels
+                .records()
                 .par_iter()
                 .filter(|v| v.address() == mailing)
-                .map(|v| v.situs())
-                .cloned()
+                .map(|v| v.situs().clone())
                 .collect::<Vec<String>>();
-            let tax_parcels = parcels
+            let (tax_parcels, parcel_situs): (Vec<String>, Vec<String>) = parcels
+                .records()
                 .par_iter()
                 .filter(|v| situs.contains(v.situs()))
-                .map(|v| v.map_number())
-                .cloned()
-                .collect::<Vec<String>>();
+                .map(|v| (v.map_number().clone(), v.situs().clone()))
+                .unzip();
             Ok(MailingListItem {
                 name: name.to_string(),
                 properties: situs.len(),
@@ -690,62 +711,31 @@ impl MailingListItem {
                 mailing_csz: FlatList::new(csz),
                 associated_names: FlatList::new(names),
                 parcels: FlatList::new(tax_parcels),
+                parcel_situs: FlatList::new(parcel_situs),
             })
         } else {
             Err(Bandage::Unknown)
         }
     }
 
-    /// Creates a new `MailingListItem` from an [`crate::import::CountyTaxlots`] struct.
+    /// Creates a new `MailingListItem` from an [`crate::import::CityTaxlots`] struct.  Wraps
+    /// [`Self::from_parcels`].
+    pub fn from_city_parcels(
+        name: &str,
+        parcels: &CityTaxlots,
+        done: &mut std::collections::HashSet<String>,
+    ) -> Clean<Self> {
+        Self::from_parcels(name, parcels, done)
+    }
+
+    /// Creates a new `MailingListItem` from an [`crate::import::CountyTaxlots`] struct.  Wraps
+    /// [`Self::from_parcels`].
     pub fn from_county_parcels(
         name: &str,
         parcels: &CountyTaxlots,
         done: &mut std::collections::HashSet<String>,
     ) -> Clean<Self> {
-        done.insert(name.to_string());
-        let addr = parcels.associated_addresses(name);
-        if !addr.is_empty() {
-            let mut csz = parcels
-                .iter()
-                .filter(|v| addr.contains(v.address()))
-                .map(|v| v.csz().clone())
-                .collect::<Vec<String>>();
-            csz.sort();
-            csz.dedup();
-            let mailing = &addr[0];
-            let names = parcels.associated_names(mailing);
-            for i in &names {
-                if !done.contains(i) {
-                    done.insert(i.to_string());
-                }
-            }
-            let names = names
-                .par_iter()
-                .filter(|v| *v != name)
-                .map(|v| v.to_string())
-                .collect::<Vec<String>>();
-            let situs = parcels
-                .par_iter()
-                .filter(|v| v.address() == mailing)
-                .map(|v| v.situs().clone())
-                .collect::<Vec<String>>();
-            let tax_parcels = parcels
-                .par_iter()
-                .filter(|v| situs.contains(v.situs()))
-                .map(|v| v.map_number().clone())
-                .collect::<Vec<String>>();
-            Ok(MailingListItem {
-                name: name.to_string(),
-                properties: situs.len(),
-                situs_addresses: FlatList::new(situs),
-                mailing_address: FlatList::new(addr),
-                mailing_csz: FlatList::new(csz),
-                associated_names: FlatList::new(names),
-                parcels: FlatList::new(tax_parcels),
-            })
-        } else {
-            Err(Bandage::Unknown)
-        }
+        Self::from_parcels(name, parcels, done)
     }
 }
 
@@ -758,26 +748,7 @@ impl TryFrom<&CityTaxlots> for MailingList {
     type Error = Bandage;
     fn try_from(parcels: &CityTaxlots) -> Clean<Self> {
         info!("Importing from city parcels.");
-        let style = indicatif::ProgressStyle::with_template(
-            "[{elapsed_precise}] {bar:40.cyan/blue} {pos:>7}/{len:7} {'Importing city parcels...'}",
-        )
-        .unwrap();
-        let mut names = parcels.owner_names();
-        names.sort();
-        names.dedup();
-        let bar = ProgressBar::new(names.len() as u64);
-        bar.set_style(style);
-        let mut records = Vec::new();
-        let mut done = std::collections::HashSet::new();
-        for name in names {
-            if !done.contains(&name) {
-                records.push(MailingListItem::from_city_parcels(
-                    &name, parcels, &mut done,
-                )?);
-            }
-            bar.inc(1);
-        }
-        Ok(MailingList(records))
+        Self::from_parcels(parcels)
     }
 }
 
@@ -785,8 +756,16 @@ impl TryFrom<&CountyTaxlots> for MailingList {
     type Error = Bandage;
     fn try_from(parcels: &CountyTaxlots) -> Clean<Self> {
         info!("Importing from county parcels.");
+        Self::from_parcels(parcels)
+    }
+}
+
+impl MailingList {
+    /// Creates a new `MailingList` from any collection of taxlots implementing [`Taxlots`], with
+    /// one [`MailingListItem`] for each unique mailing address.
+    pub fn from_parcels<U: Taxlots>(parcels: &U) -> Clean<Self> {
         let style = indicatif::ProgressStyle::with_template(
-            "[{elapsed_precise}] {bar:40.cyan/blue} {pos:>7}/{len:7} {'Importing county parcels...'}",
+            "[{elapsed_precise}] {bar:40.cyan/blue} {pos:>7}/{len:7} {'Importing parcels...'}",
         )
         .unwrap();
         let mut names = parcels.owner_names();
@@ -798,14 +777,127 @@ impl TryFrom<&CountyTaxlots> for MailingList {
         let mut done = std::collections::HashSet::new();
         for name in names {
             if !done.contains(&name) {
-                records.push(MailingListItem::from_county_parcels(
-                    &name, parcels, &mut done,
-                )?);
+                records.push(MailingListItem::from_parcels(&name, parcels, &mut done)?);
             }
             bar.inc(1);
         }
         Ok(MailingList(records))
     }
+
+    /// Creates a new `MailingList` from the taxlots in `parcels` that satisfy `query`.  The
+    /// property count of each owner includes all taxlots in `parcels` sent to the mailing address,
+    /// not only those passing the filters.  If the query sets a minimum property count, owners
+    /// with fewer properties are removed from the list.
+    pub fn from_query<U: Taxlots + From<Vec<U::Item>>>(
+        parcels: &U,
+        query: &TaxlotQuery,
+    ) -> Clean<Self> {
+        let mut counts = std::collections::HashMap::new();
+        for lot in parcels.records() {
+            *counts.entry(lot.address().clone()).or_insert(0) += 1;
+        }
+        let filtered = parcels.query(query);
+        info!("Taxlots matching query: {}", filtered.records().len());
+        let mut list = Self::from_parcels(&filtered)?;
+        for item in list.iter_mut() {
+            if let Some(address) = item.mailing_address.0.first() {
+                item.properties = counts.get(address).copied().unwrap_or(item.properties);
+            }
+        }
+        if let Some(count) = query.min_properties() {
+            list.retain(|v| v.properties() >= count);
+        }
+        Ok(list)
+    }
+
+    /// Write the contents of `MailingList` to a JSON file at location `path`.  Unlike
+    /// [`MailingListExport`], the situs addresses, mailing addresses, associated names and parcels
+    /// of each [`MailingListItem`] are written as arrays rather than joined into a single string.
+    pub fn to_json<P: AsRef<std::path::Path>>(&self, path: P) -> Clean<()> {
+        let contents =
+            serde_json::to_vec_pretty(&self.0).map_err(|e| Bandage::Hint(e.to_string()))?;
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+}
+
+/// The `MailingListRow` struct holds a single owner and parcel from a [`MailingListItem`].  The
+/// `owner_group` field identifies the mailing address the row came from, so that rows belonging to
+/// the same mailing address can be grouped back together after export.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, derive_getters::Getters)]
+pub struct MailingListRow {
+    owner_group: String,
+    name: String,
+    mailing_address: String,
+    mailing_csz: String,
+    parcel: String,
+    situs_address: String,
+}
+
+impl MailingListRow {
+    /// Creates the rows for a [`MailingListItem`], one for each pair of owner and parcel.  The
+    /// owners are the owner of the item followed by the other owners sharing the mailing address,
+    /// and every row of the item has the same `owner_group`.
+    pub fn from_item(item: &MailingListItem) -> Vec<Self> {
+        let mailing_address = item.mailing_address.0.first().cloned().unwrap_or_default();
+        let mailing_csz = item.mailing_csz.0.first().cloned().unwrap_or_default();
+        let owner_group = Self::owner_group(&item.name, &mailing_address, &mailing_csz);
+        std::iter::once(&item.name)
+            .chain(item.associated_names.0.iter())
+            .flat_map(|name| {
+                item.parcels.0.iter().zip(item.parcel_situs.0.iter()).map(
+                    |(parcel, situs_address)| MailingListRow {
+                        owner_group: owner_group.clone(),
+                        name: name.clone(),
+                        mailing_address: mailing_address.clone(),
+                        mailing_csz: mailing_csz.clone(),
+                        parcel: parcel.clone(),
+                        situs_address: situs_address.clone(),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    /// The `owner_group` method returns the group identifier for the owner `name` at a mailing
+    /// address, a 64-bit FNV-1a hash of the normalized name, address and city/state/zip in
+    /// hexadecimal.  The identifier depends only on these fields, so an owner keeps the same
+    /// identifier when other taxlots are added to or removed from the list.
+    pub fn owner_group(name: &str, address: &str, csz: &str) -> String {
+        let key = [name, address, csz]
+            .iter()
+            .map(|v| utils::normalize(v))
+            .collect::<Vec<String>>()
+            .join("|");
+        let hash = key.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+        format!("{:016x}", hash)
+    }
+}
+
+/// The `MailingListRows` struct holds a [`MailingList`] in normalized long format, with one
+/// [`MailingListRow`] for each owner and parcel.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Deref, DerefMut)]
+pub struct MailingListRows(Vec<MailingListRow>);
+
+impl MailingListRows {
+    /// Write the contents of `MailingListRows` to a CSV file at location `path`.  Each element in
+    /// the vector of type [`MailingListRow`] maps to a row of data on the CSV.
+    pub fn to_csv<P: AsRef<std::path::Path>>(&mut self, path: P) -> Clean<()> {
+        to_csv(self, path)?;
+        Ok(())
+    }
+}
+
+impl From<&MailingList> for MailingListRows {
+    fn from(items: &MailingList) -> Self {
+        let records = items
+            .iter()
+            .flat_map(MailingListRow::from_item)
+            .collect::<Vec<MailingListRow>>();
+        MailingListRows(records)
+    }
 }
 
 /// The `MailingListExportItem` struct holds mailing list data in export format.
@@ -0,0 +1,273 @@
+//! The `geocode` module attaches coordinates from an address record to any record type with an
+//! address column, such as taxlot situs addresses, business licenses or a user CSV.  Every record
+//! is kept in the report, including those whose address does not parse.
+use crate::import::{CityTaxlot, CountyTaxlot, Taxlot};
+use crate::utils;
+use address::{Address, Addresses, MatchPartialRecord, MatchStatus};
+use aid::prelude::*;
+use derive_more::{Deref, DerefMut};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The `Geocodable` trait identifies the address of a record to geocode.
+pub trait Geocodable {
+    /// An identifier for the record, used to join the results back to the source data.
+    fn geocode_id(&self) -> String;
+    /// The address text to parse and match.
+    fn geocode_address(&self) -> String;
+}
+
+impl Geocodable for CountyTaxlot {
+    /// Geocodes the situs address, identified by the account number.
+    fn geocode_id(&self) -> String {
+        self.account().clone()
+    }
+
+    fn geocode_address(&self) -> String {
+        self.situs().clone()
+    }
+}
+
+impl Geocodable for CityTaxlot {
+    /// Geocodes the situs address, identified by the account number.
+    fn geocode_id(&self) -> String {
+        self.account().clone()
+    }
+
+    fn geocode_address(&self) -> String {
+        self.situs().clone()
+    }
+}
+
+/// The `CsvAddress` struct holds the address from a named column of a CSV file, identified by its
+/// row number.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CsvAddress {
+    /// The row number of the record, starting from one for the first row after the header.
+    pub row: usize,
+    /// The value of the address column.
+    pub address: String,
+}
+
+impl Geocodable for CsvAddress {
+    fn geocode_id(&self) -> String {
+        self.row.to_string()
+    }
+
+    fn geocode_address(&self) -> String {
+        self.address.clone()
+    }
+}
+
+/// The `MatchOutcome` enum classifies the result of matching an address against an address
+/// record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum MatchOutcome {
+    /// The address parsed and matched an address in the record.
+    Matched,
+    /// The address parsed and matched an address in the record on some, but not all, elements.
+    PartialMatch,
+    /// The address parsed, but did not match any address in the record.
+    NoMatch,
+    /// The address could not be parsed.
+    Unparseable,
+    /// The record could not be read from the source file.
+    Malformed,
+}
+
+/// The `Geocode` struct holds the result of geocoding a single record.  Coordinates are present
+/// when the address matched fully or partially.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Geocode {
+    /// The identifier of the source record.
+    pub id: String,
+    /// The raw address text of the source record.
+    pub input: String,
+    /// The classification of the result.
+    pub outcome: MatchOutcome,
+    /// The match status of the closest address, if the input parsed.
+    pub match_status: Option<MatchStatus>,
+    /// The label of the matched address.
+    pub address_label: Option<String>,
+    /// The x coordinate (longitude) of the matched address.
+    pub x_coordinate: Option<f64>,
+    /// The y coordinate (latitude) of the matched address.
+    pub y_coordinate: Option<f64>,
+    /// The parse error, if the input could not be parsed as an address, or the read error of a
+    /// malformed record.
+    pub error: Option<String>,
+}
+
+impl Geocode {
+    /// Creates a new `Geocode` by parsing the address of `record` and matching it against
+    /// `addresses`.
+    pub fn new<
+        T: Geocodable,
+        A: Address + Clone + Send + Sync + galileo::galileo_types::geo::GeoPoint<Num = f64>,
+        U: Addresses<A>,
+    >(
+        record: &T,
+        addresses: &U,
+    ) -> Self {
+        let input = record.geocode_address();
+        let mut item = Self {
+            id: record.geocode_id(),
+            input: input.clone(),
+            outcome: MatchOutcome::Unparseable,
+            match_status: None,
+            address_label: None,
+            x_coordinate: None,
+            y_coordinate: None,
+            error: None,
+        };
+        match address::Parser::address(&input) {
+            Ok((_, address)) => {
+                let matches = MatchPartialRecord::compare(&address, addresses);
+                item.outcome = MatchOutcome::NoMatch;
+                if let Some(record) = utils::best_match(&matches) {
+                    item.match_status = Some(record.match_status());
+                    let outcome = match record.match_status() {
+                        MatchStatus::Matching => Some(MatchOutcome::Matched),
+                        MatchStatus::Divergent => Some(MatchOutcome::PartialMatch),
+                        _ => None,
+                    };
+                    if let Some(outcome) = outcome {
+                        item.outcome = outcome;
+                        item.address_label = Some(record.address_label());
+                        item.x_coordinate = record.longitude();
+                        item.y_coordinate = record.latitude();
+                    }
+                }
+            }
+            Err(e) => {
+                tracing::trace!("Could not parse {}: {}", &input, e.to_string());
+                item.error = Some(e.to_string());
+            }
+        }
+        item
+    }
+
+    /// Creates a new `Geocode` with identifier `id` for a record that could not be read, with the
+    /// read error `error`.
+    pub fn malformed(id: &str, error: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            input: String::new(),
+            outcome: MatchOutcome::Malformed,
+            match_status: None,
+            address_label: None,
+            x_coordinate: None,
+            y_coordinate: None,
+            error: Some(error.to_string()),
+        }
+    }
+}
+
+/// The `Geocodes` struct holds a vector of type [`Geocode`], one for each record geocoded.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Deref, DerefMut)]
+pub struct Geocodes(Vec<Geocode>);
+
+impl Geocodes {
+    /// Geocodes each record in `records` against `addresses`.
+    pub fn from_records<
+        T: Geocodable,
+        A: Address + Clone + Send + Sync + galileo::galileo_types::geo::GeoPoint<Num = f64>,
+        U: Addresses<A>,
+    >(
+        records: &[T],
+        addresses: &U,
+    ) -> Self {
+        let style = indicatif::ProgressStyle::with_template(
+            "[{elapsed_precise}] {bar:40.cyan/blue} {pos:>7}/{len:7} {'Geocoding addresses.'}",
+        )
+        .unwrap();
+        let bar = indicatif::ProgressBar::new(records.len() as u64);
+        bar.set_style(style);
+        let mut results = Vec::new();
+        for record in records {
+            results.push(Geocode::new(record, addresses));
+            bar.inc(1);
+        }
+        Self(results)
+    }
+
+    /// Geocodes the values in the column named `column` of the CSV file at location `path`.
+    /// Rows that cannot be read are reported with outcome [`MatchOutcome::Malformed`].  Returns an
+    /// error if the file has no column named `column`.
+    pub fn from_csv<
+        P: AsRef<std::path::Path>,
+        A: Address + Clone + Send + Sync + galileo::galileo_types::geo::GeoPoint<Num = f64>,
+        U: Addresses<A>,
+    >(
+        path: P,
+        column: &str,
+        addresses: &U,
+    ) -> Clean<Self> {
+        let file = std::fs::File::open(path)?;
+        let mut rdr = csv::Reader::from_reader(file);
+        let index = rdr
+            .headers()
+            .map_err(|e| Bandage::Hint(e.to_string()))?
+            .iter()
+            .position(|v| v.trim() == column)
+            .ok_or_else(|| Bandage::Hint(format!("Column {} not found.", column)))?;
+        let mut records = Vec::new();
+        let mut malformed = Vec::new();
+        for (i, result) in rdr.records().enumerate() {
+            match result {
+                Ok(row) => records.push(CsvAddress {
+                    row: i + 1,
+                    address: row.get(index).unwrap_or_default().to_string(),
+                }),
+                Err(e) => {
+                    tracing::trace!("Malformed row {}: {}", i + 1, e.to_string());
+                    malformed.push(Geocode::malformed(&(i + 1).to_string(), &e.to_string()));
+                }
+            }
+        }
+        let mut report = Self::from_records(&records, addresses);
+        report.extend(malformed);
+        report.sort_by_key(|v| v.id.parse::<usize>().unwrap_or_default());
+        Ok(report)
+    }
+
+    /// The `outcome` method returns the records with outcome `outcome`.
+    pub fn outcome(&self, outcome: MatchOutcome) -> Self {
+        let records = self
+            .iter()
+            .filter(|v| v.outcome == outcome)
+            .cloned()
+            .collect::<Vec<Geocode>>();
+        Self(records)
+    }
+
+    /// The `counts` method returns the number of records with each outcome.
+    pub fn counts(&self) -> BTreeMap<MatchOutcome, usize> {
+        let mut counts = BTreeMap::new();
+        for item in self.iter() {
+            *counts.entry(item.outcome).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// The `status_counts` method returns the number of records with each [`MatchStatus`] of the
+    /// closest address.  Records without a match status are counted under their outcome.
+    pub fn status_counts(&self) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::new();
+        for item in self.iter() {
+            let key = match item.match_status {
+                Some(status) => format!("{:?}", status),
+                None => format!("{:?}", item.outcome),
+            };
+            *counts.entry(key).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Write the contents of `Geocodes` to a CSV file at location `path`.  Each element in the
+    /// vector of type [`Geocode`] maps to a row of data on the CSV.
+    pub fn to_csv<P: AsRef<std::path::Path>>(&mut self, path: P) -> Clean<()> {
+        utils::to_csv(self, path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,314 @@
+//! The `geography` module assigns matched survey responses to polygon areas, such as wards, ZIP
+//! codes or neighborhoods, and tabulates the answers to a question in each area.
+use crate::convert::Convert;
+use crate::import::SurveyExport;
+use aid::prelude::*;
+use derive_more::{Deref, DerefMut, From};
+use geo::Contains;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+
+/// The z-score used for the margin of error, giving a 95 percent confidence interval.
+const Z_95: f64 = 1.96;
+
+/// The `Area` struct holds a named polygon area.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Area {
+    /// The name of the area, read from the name field of the layer.
+    pub name: String,
+    /// The boundary of the area.
+    pub geometry: geo::MultiPolygon,
+}
+
+/// The `Areas` struct holds a vector of type [`Area`].  The layer must use the same coordinate
+/// system as the points assigned to it, which for matched survey responses is longitude and
+/// latitude.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Deref, DerefMut, From)]
+pub struct Areas(Vec<Area>);
+
+impl Areas {
+    /// Reads the areas from a shapefile if `path` has a .shp extension, otherwise from a GeoJSON
+    /// file.  The area names are read from the field or property named `field`.
+    pub fn from_path<P: AsRef<std::path::Path>>(path: P, field: &str) -> Clean<Self> {
+        if path.as_ref().extension().and_then(|v| v.to_str()) == Some("shp") {
+            Self::from_shp(path, field)
+        } else {
+            Self::from_geojson(path, field)
+        }
+    }
+
+    /// Reads the polygon areas from the shapefile at `path`, naming each area by the value of the
+    /// attribute `field`.  Shapes that are not polygons are skipped.  Returns an error if an area
+    /// name is blank or repeated, see [`Self::check`].
+    pub fn from_shp<P: AsRef<std::path::Path>>(path: P, field: &str) -> Clean<Self> {
+        let shapes = shapefile::read(path).map_err(|e| Bandage::Hint(e.to_string()))?;
+        let mut records = Vec::new();
+        for (shape, record) in shapes {
+            let polygons = match shape {
+                shapefile::Shape::Polygon(polygon) => Convert::new(polygon).geo_polygons(),
+                shapefile::Shape::PolygonZ(polygon) => Convert::new(polygon).geo_polygons(),
+                _ => {
+                    tracing::trace!("Skipping shape that is not a polygon.");
+                    continue;
+                }
+            };
+            let name = match record.get(field) {
+                Some(shapefile::dbase::FieldValue::Character(Some(value))) => value.trim().into(),
+                Some(shapefile::dbase::FieldValue::Numeric(Some(value))) => value.to_string(),
+                _ => String::new(),
+            };
+            records.push(Area {
+                name,
+                geometry: geo::MultiPolygon::new(polygons),
+            });
+        }
+        tracing::info!("Areas read: {}", records.len());
+        let areas = Self(records);
+        areas.check(field)?;
+        Ok(areas)
+    }
+
+    /// Reads the polygon areas from the GeoJSON file at `path`, naming each area by the value of
+    /// the property `field`.  Features that are not polygons are skipped.  Returns an error if an
+    /// area name is blank or repeated, see [`Self::check`].
+    pub fn from_geojson<P: AsRef<std::path::Path>>(path: P, field: &str) -> Clean<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        let collection = contents
+            .parse::<geojson::GeoJson>()
+            .and_then(geojson::FeatureCollection::try_from)
+            .map_err(|e| Bandage::Hint(e.to_string()))?;
+        let mut records = Vec::new();
+        for feature in collection.features {
+            let geometry = match feature
+                .geometry
+                .as_ref()
+                .map(|v| geo::Geometry::try_from(v.value.clone()))
+            {
+                Some(Ok(geo::Geometry::Polygon(polygon))) => geo::MultiPolygon::new(vec![polygon]),
+                Some(Ok(geo::Geometry::MultiPolygon(polygons))) => polygons,
+                _ => {
+                    tracing::trace!("Skipping feature that is not a polygon.");
+                    continue;
+                }
+            };
+            let name = match feature.property(field) {
+                Some(serde_json::Value::String(value)) => value.trim().to_string(),
+                Some(serde_json::Value::Null) | None => String::new(),
+                Some(value) => value.to_string(),
+            };
+            records.push(Area { name, geometry });
+        }
+        tracing::info!("Areas read: {}", records.len());
+        let areas = Self(records);
+        areas.check(field)?;
+        Ok(areas)
+    }
+
+    /// The `check` method returns an error if an area has a blank name, or shares its name with
+    /// another area.  [`CrossTab`] tallies responses by area name, so such areas would be merged
+    /// into one row.  Multipart areas should be dissolved into a single feature before reading.
+    /// The name `field` is used in the error message.
+    pub fn check(&self, field: &str) -> Clean<()> {
+        let mut seen = BTreeSet::new();
+        for area in self.iter() {
+            if area.name.is_empty() {
+                return Err(Bandage::Hint(format!(
+                    "Area without a value for {}.",
+                    field
+                )));
+            }
+            if !seen.insert(area.name.as_str()) {
+                return Err(Bandage::Hint(format!(
+                    "Duplicate area {} in {}.",
+                    area.name, field
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// The `locate` method returns the name of the first area containing `point`, if any.
+    pub fn locate(&self, point: &geo::Point) -> Option<&str> {
+        self.iter()
+            .find(|v| v.geometry.contains(point))
+            .map(|v| v.name.as_str())
+    }
+}
+
+/// The `CrossTabRow` struct holds the tally of answers to a question in one area.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CrossTabRow {
+    /// The name of the area.
+    pub area: String,
+    /// The total weight of responses in the area.
+    pub total: f64,
+    /// The sum of the squared weights of responses in the area.
+    pub total_squared: f64,
+    /// The weighted count of each answer, in the order of [`CrossTab::answers`].
+    pub counts: Vec<f64>,
+}
+
+impl CrossTabRow {
+    /// The `share` method returns the share of responses in the area for the answer at `index`.
+    pub fn share(&self, index: usize) -> f64 {
+        if self.total > 0. {
+            self.counts[index] / self.total
+        } else {
+            0.
+        }
+    }
+
+    /// The `effective_size` method returns the effective sample size of the weighted responses
+    /// in the area, (Σw)² / Σw².  With unit weights this is the number of responses, and
+    /// down-weighted duplicates count for less than one response each.
+    pub fn effective_size(&self) -> f64 {
+        if self.total_squared > 0. {
+            self.total * self.total / self.total_squared
+        } else {
+            0.
+        }
+    }
+
+    /// The `margin_of_error` method returns the margin of error of the share for the answer at
+    /// `index`, at 95 percent confidence using the normal approximation with the effective sample
+    /// size.
+    pub fn margin_of_error(&self, index: usize) -> f64 {
+        let size = self.effective_size();
+        if size > 0. {
+            let share = self.share(index);
+            Z_95 * (share * (1. - share) / size).sqrt()
+        } else {
+            0.
+        }
+    }
+}
+
+/// The `CrossTab` struct tabulates the answers to a survey question by area.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CrossTab {
+    /// The survey question tabulated.
+    pub question: String,
+    /// The answers observed, sorted alphabetically.
+    pub answers: Vec<String>,
+    /// The tally for each area with responses, sorted by area name.
+    pub rows: Vec<CrossTabRow>,
+}
+
+impl CrossTab {
+    /// Creates a new `CrossTab` by assigning each response in `export` to an area in `areas` and
+    /// tallying the answers to `question`.  Responses are counted by their weight, and responses
+    /// outside of every area are skipped.  Areas are tallied by name, so the names in `areas`
+    /// should be unique, see [`Areas::check`].
+    pub fn new(export: &SurveyExport, question: &str, areas: &Areas) -> Self {
+        let answers = export
+            .records
+            .iter()
+            .filter_map(|v| v.answers.get(question).cloned())
+            .collect::<BTreeSet<String>>()
+            .into_iter()
+            .collect::<Vec<String>>();
+        let mut tallies: BTreeMap<String, CrossTabRow> = BTreeMap::new();
+        let mut outside = 0;
+        for item in &export.records {
+            let answer = match item.answers.get(question) {
+                Some(answer) => answer,
+                None => continue,
+            };
+            let point = geo::Point::new(item.x_coordinate, item.y_coordinate);
+            match areas.locate(&point) {
+                Some(area) => {
+                    let row = tallies.entry(area.to_string()).or_insert(CrossTabRow {
+                        area: area.to_string(),
+                        total: 0.,
+                        total_squared: 0.,
+                        counts: vec![0.; answers.len()],
+                    });
+                    if let Some(index) = answers.iter().position(|v| v == answer) {
+                        row.counts[index] += item.weight;
+                        row.total += item.weight;
+                        row.total_squared += item.weight * item.weight;
+                    }
+                }
+                None => outside += 1,
+            }
+        }
+        tracing::info!("{} responses outside of any area.", outside);
+        Self {
+            question: question.to_string(),
+            answers,
+            rows: tallies.into_values().collect(),
+        }
+    }
+
+    /// Write the `CrossTab` to a CSV file at location `path`, with one row for each area and a
+    /// count, percentage and margin of error column for each answer.
+    pub fn to_csv<P: AsRef<std::path::Path>>(&self, path: P) -> Clean<()> {
+        let mut wtr = csv::Writer::from_writer(std::fs::File::create(path)?);
+        let mut header = vec!["area".to_string(), "total".to_string()];
+        for answer in &self.answers {
+            header.push(format!("{} count", answer));
+            header.push(format!("{} percent", answer));
+            header.push(format!("{} moe", answer));
+        }
+        wtr.write_record(&header)
+            .map_err(|e| Bandage::Hint(e.to_string()))?;
+        for row in &self.rows {
+            let mut record = vec![row.area.clone(), row.total.to_string()];
+            for i in 0..self.answers.len() {
+                record.push(row.counts[i].to_string());
+                record.push((100. * row.share(i)).to_string());
+                record.push((100. * row.margin_of_error(i)).to_string());
+            }
+            wtr.write_record(&record)
+                .map_err(|e| Bandage::Hint(e.to_string()))?;
+        }
+        wtr.flush()?;
+        Ok(())
+    }
+
+    /// The `feature_collection` method converts the `CrossTab` into a
+    /// [`geojson::FeatureCollection`] of the polygons in `areas`, with the tallies for each area
+    /// as properties.
+    pub fn feature_collection(&self, areas: &Areas) -> geojson::FeatureCollection {
+        let rows = self
+            .rows
+            .iter()
+            .map(|v| (v.area.as_str(), v))
+            .collect::<BTreeMap<&str, &CrossTabRow>>();
+        areas
+            .iter()
+            .map(|area| {
+                let geometry = geo::Geometry::MultiPolygon(area.geometry.clone());
+                let mut feature = Convert::new(geometry).geojson_feature();
+                feature.set_property("area", area.name.clone());
+                let row = rows.get(area.name.as_str());
+                feature.set_property("total", row.map(|v| v.total).unwrap_or_default());
+                for (i, answer) in self.answers.iter().enumerate() {
+                    feature.set_property(
+                        format!("{} count", answer),
+                        row.map(|v| v.counts[i]).unwrap_or_default(),
+                    );
+                    feature.set_property(
+                        format!("{} percent", answer),
+                        row.map(|v| 100. * v.share(i)).unwrap_or_default(),
+                    );
+                    feature.set_property(
+                        format!("{} moe", answer),
+                        row.map(|v| 100. * v.margin_of_error(i)).unwrap_or_default(),
+                    );
+                }
+                feature
+            })
+            .collect()
+    }
+
+    /// The `geojson` method writes the polygons in `areas`, with the tallies for each area as
+    /// properties, to a GeoJSON file at location `path`.
+    pub fn geojson<P: AsRef<std::path::Path>>(&self, areas: &Areas, path: P) -> Clean<()> {
+        let contents = self.feature_collection(areas).to_string().into_bytes();
+        let mut file = std::fs::File::create(path)?;
+        file.write_all(&contents)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,273 @@
+use crate::import::bea::{BeaData, BeaTable};
+use crate::prelude::*;
+use aid::prelude::*;
+use derive_more::{Deref, DerefMut};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// The FIPS number BEA uses for the United States.
+pub const US_FIPS: i32 = 0;
+/// The FIPS number BEA uses for the state of Oregon.
+pub const OREGON_FIPS: i32 = 41000;
+
+/// The first NAICS industry line in CAINC5N and CAEMP25N.  The lines before it are income
+/// components, employment types and aggregates such as private nonfarm earnings.
+const FIRST_INDUSTRY_LINE: u32 = 100;
+
+/// The `BeaAnalysis` struct holds the parameters for comparing the industries of a target area
+/// against a benchmark area, such as a county against the nation.  Industries are the NAICS
+/// industry lines, from line 100 on, of the CAINC5N or CAEMP25N table of the `total` line that
+/// share its unit of measure.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BeaAnalysis {
+    /// The FIPS number of the area of interest.
+    pub target: i32,
+    /// The FIPS number of the benchmark area, e.g. [`US_FIPS`] or [`OREGON_FIPS`].
+    pub benchmark: i32,
+    /// The first year of the comparison.
+    pub start: i32,
+    /// The last year of the comparison.
+    pub end: i32,
+    /// The line code of the total the industries are shares of.
+    pub total: String,
+}
+
+impl BeaAnalysis {
+    /// Creates a new `BeaAnalysis` comparing `target` to `benchmark` from `start` to `end`, with
+    /// shares of total earnings by place of work in CAINC5N.
+    pub fn new(target: i32, benchmark: i32, start: i32, end: i32) -> Self {
+        Self {
+            target,
+            benchmark,
+            start,
+            end,
+            total: "CAINC5N-35".to_string(),
+        }
+    }
+
+    /// Sets the line code of the total the industries are shares of, e.g. "CAEMP25N-10" for
+    /// total employment.
+    pub fn with_total(mut self, code: &str) -> Self {
+        self.total = code.trim().to_string();
+        self
+    }
+
+    /// The `industries` method returns the line codes and descriptions of the industries in
+    /// `data`, as defined for [`BeaAnalysis`].
+    fn industries(&self, data: &BeaData) -> Clean<BTreeMap<String, String>> {
+        let (table, _) = BeaTable::parse_code(&self.total)?;
+        if !matches!(table, BeaTable::Cainc5n | BeaTable::Caemp25n) {
+            return Err(Bandage::Hint(format!(
+                "No NAICS industry lines in table {}.",
+                table
+            )));
+        }
+        let measure = data
+            .iter()
+            .find(|v| v.code == self.total)
+            .map(|v| v.measure())
+            .ok_or_else(|| Bandage::Hint(format!("No records for total {}.", self.total)))?;
+        Ok(data
+            .iter()
+            .filter(|v| matches!(v.table(), Ok(t) if t == table))
+            .filter(|v| matches!(v.line_code(), Ok(l) if l >= FIRST_INDUSTRY_LINE))
+            .filter(|v| v.measure() == measure)
+            .map(|v| (v.code.clone(), v.description.clone()))
+            .collect())
+    }
+
+    /// The `location_quotients` method returns the location quotient of each industry in the
+    /// target area, in both the start and end years.  Industries with a missing value in either
+    /// area are skipped.
+    pub fn location_quotients(&self, data: &BeaData) -> Clean<LocationQuotients> {
+        let values = Values::new(data, &[self.target, self.benchmark]);
+        let mut records = Vec::new();
+        for (code, description) in self.industries(data)? {
+            for year in [self.start, self.end] {
+                let local = values.get(self.target, &code, year);
+                let local_total = values.get(self.target, &self.total, year);
+                let benchmark = values.get(self.benchmark, &code, year);
+                let benchmark_total = values.get(self.benchmark, &self.total, year);
+                if let (Some(local), Some(local_total), Some(benchmark), Some(benchmark_total)) =
+                    (local, local_total, benchmark, benchmark_total)
+                {
+                    records.push(LocationQuotient::new(
+                        &code,
+                        &description,
+                        year,
+                        (local, local_total),
+                        (benchmark, benchmark_total),
+                    ));
+                } else {
+                    tracing::trace!("Skipping {} in {}: missing values.", code, year);
+                }
+            }
+        }
+        Ok(LocationQuotients(records))
+    }
+
+    /// The `shift_share` method decomposes the change in each industry of the target area from
+    /// the start year to the end year into national growth, industry mix and regional
+    /// competitive components, using the benchmark area as the nation.  Industries with a missing
+    /// value in either area or year are skipped.
+    pub fn shift_share(&self, data: &BeaData) -> Clean<ShiftShares> {
+        let values = Values::new(data, &[self.target, self.benchmark]);
+        let growth = match (
+            values.get(self.benchmark, &self.total, self.start),
+            values.get(self.benchmark, &self.total, self.end),
+        ) {
+            (Some(start), Some(end)) if start != 0. => end / start - 1.,
+            _ => {
+                return Err(Bandage::Hint(format!(
+                    "Missing total {} for benchmark {}.",
+                    self.total, self.benchmark
+                )))
+            }
+        };
+        let mut records = Vec::new();
+        for (code, description) in self.industries(data)? {
+            let local_start = values.get(self.target, &code, self.start);
+            let local_end = values.get(self.target, &code, self.end);
+            let benchmark_start = values.get(self.benchmark, &code, self.start);
+            let benchmark_end = values.get(self.benchmark, &code, self.end);
+            match (local_start, local_end, benchmark_start, benchmark_end) {
+                (Some(start), Some(end), Some(benchmark_start), Some(benchmark_end))
+                    if benchmark_start != 0. =>
+                {
+                    let industry_growth = benchmark_end / benchmark_start - 1.;
+                    let national_growth = start * growth;
+                    let industry_mix = start * (industry_growth - growth);
+                    let change = end - start;
+                    records.push(ShiftShare {
+                        code,
+                        description,
+                        start,
+                        end,
+                        change,
+                        national_growth,
+                        industry_mix,
+                        competitive: change - national_growth - industry_mix,
+                    });
+                }
+                _ => tracing::trace!("Skipping {}: missing values.", code),
+            }
+        }
+        Ok(ShiftShares(records))
+    }
+}
+
+/// Holds the values of the records for a set of areas, keyed by FIPS number, line code and year.
+struct Values(HashMap<(i32, String, i32), f64>);
+
+impl Values {
+    fn new(data: &BeaData, fips: &[i32]) -> Self {
+        Self(
+            data.iter()
+                .filter(|v| fips.contains(&v.geo_fips))
+                .filter_map(|v| {
+                    v.value()
+                        .map(|value| ((v.geo_fips, v.code.clone(), v.time_period), value))
+                })
+                .collect(),
+        )
+    }
+
+    fn get(&self, fips: i32, code: &str, year: i32) -> Option<f64> {
+        self.0.get(&(fips, code.to_string(), year)).copied()
+    }
+}
+
+/// The `LocationQuotient` struct holds the location quotient of one industry in one year, the
+/// share of the industry in the target area divided by its share in the benchmark area.  A
+/// quotient above one means the industry is more concentrated in the target area.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LocationQuotient {
+    /// The BEA line code of the industry.
+    pub code: String,
+    /// The description of the industry.
+    pub description: String,
+    /// The year of the values.
+    pub year: i32,
+    /// The share of the industry in the total of the target area.
+    pub local_share: f64,
+    /// The share of the industry in the total of the benchmark area.
+    pub benchmark_share: f64,
+    /// The location quotient, or `None` if the benchmark share is zero.
+    pub quotient: Option<f64>,
+}
+
+impl LocationQuotient {
+    /// Creates a new `LocationQuotient` from the industry value and total of the target area in
+    /// `local`, and of the benchmark area in `benchmark`.
+    pub fn new(
+        code: &str,
+        description: &str,
+        year: i32,
+        local: (f64, f64),
+        benchmark: (f64, f64),
+    ) -> Self {
+        let share = |(value, total): (f64, f64)| if total != 0. { value / total } else { 0. };
+        let local_share = share(local);
+        let benchmark_share = share(benchmark);
+        let quotient = if benchmark_share != 0. {
+            Some(local_share / benchmark_share)
+        } else {
+            None
+        };
+        Self {
+            code: code.to_string(),
+            description: description.to_string(),
+            year,
+            local_share,
+            benchmark_share,
+            quotient,
+        }
+    }
+}
+
+/// The `LocationQuotients` struct holds a vector of type [`LocationQuotient`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Deref, DerefMut)]
+pub struct LocationQuotients(Vec<LocationQuotient>);
+
+impl LocationQuotients {
+    /// Writes the location quotients to a CSV file at location `path`.
+    pub fn to_csv<P: AsRef<std::path::Path>>(&mut self, path: P) -> Clean<()> {
+        to_csv(self, path)?;
+        Ok(())
+    }
+}
+
+/// The `ShiftShare` struct holds the shift-share decomposition of the change in one industry of
+/// the target area.  The national growth, industry mix and competitive components sum to the
+/// change.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ShiftShare {
+    /// The BEA line code of the industry.
+    pub code: String,
+    /// The description of the industry.
+    pub description: String,
+    /// The value in the start year.
+    pub start: f64,
+    /// The value in the end year.
+    pub end: f64,
+    /// The change in value from the start year to the end year.
+    pub change: f64,
+    /// The change expected had the industry grown at the rate of the benchmark total.
+    pub national_growth: f64,
+    /// The change due to the industry growing faster or slower than the benchmark total.
+    pub industry_mix: f64,
+    /// The change due to the industry growing faster or slower locally than in the benchmark.
+    pub competitive: f64,
+}
+
+/// The `ShiftShares` struct holds a vector of type [`ShiftShare`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Deref, DerefMut)]
+pub struct ShiftShares(Vec<ShiftShare>);
+
+impl ShiftShares {
+    /// Writes the shift-share table to a CSV file at location `path`.
+    pub fn to_csv<P: AsRef<std::path::Path>>(&mut self, path: P) -> Clean<()> {
+        to_csv(self, path)?;
+        Ok(())
+    }
+}
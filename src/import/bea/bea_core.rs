@@ -1,29 +1,25 @@
+use crate::import::bea::{
+    BeaDerived, BeaMeasure, BeaPivot, BeaRegions, BeaTable, BeaValue, FipsCatalog, RegionMissing,
+};
 use crate::prelude::*;
 use aid::prelude::*;
 use derive_more::{Deref, DerefMut};
 use indicatif::{ProgressBar, ProgressStyle};
-use nom::branch::alt;
-use nom::bytes::complete::tag;
-use nom::character::complete::digit1;
-use nom::character::is_digit;
-use nom::IResult;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap},
     fmt,
     path::Path,
     time::Duration,
 };
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
-use tracing::{info, trace};
+use tracing::{info, trace, warn};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd)]
 #[serde(rename_all = "PascalCase")]
 /// The `BeaDatumRaw` struct holds a record from the BEA website API before processing.
 pub struct BeaDatumRaw {
-    // #[serde(deserialize_with = "crate::import::deserialize_code_keys")]
-    // code: Cainc5nCodeKey,
     /// The `code` field represents the BEA table code.
     pub code: String,
     /// The `geo_fips` field represents the FIPS number of the datum.
@@ -71,6 +67,19 @@ impl BeaDataRaw {
         bar.finish_with_message("Loaded!");
         Ok(BeaDataRaw(records))
     }
+
+    /// This method loads the raw downloads of each table in `tables` into a single `BeaDataRaw`.
+    /// The path to each download is read from the environmental variable named by
+    /// [`BeaTable::raw_var`], e.g. "BEA_CAINC1_RAW".
+    pub fn from_tables(tables: &[BeaTable]) -> Clean<Self> {
+        let mut records = Vec::new();
+        for table in tables {
+            let path = std::env::var(table.raw_var())?;
+            info!("Reading {} from {}.", table, &path);
+            records.extend(Self::from_csv(path)?.0);
+        }
+        Ok(BeaDataRaw(records))
+    }
 }
 
 /// The `BeaColumns` enum delineates fields in [`BeaDatum`] intended for display in a table.
@@ -120,8 +129,6 @@ impl fmt::Display for BeaColumns {
 #[serde(rename_all = "PascalCase")]
 /// The `BeaDatum` struct holds data processed from a [`BeaDatumRaw`] struct.
 pub struct BeaDatum {
-    // #[serde(deserialize_with = "crate::import::deserialize_code_keys")]
-    // code: Cainc5nCodeKey,
     /// The `code` field represents the BEA table code.
     pub code: String,
     /// The `geo_fips` field represents the FIPS number of the datum.
@@ -136,11 +143,22 @@ pub struct BeaDatum {
     pub cl_unit: String,
     /// The `unit_mult` is the numeric factor representation of the unit of measure.
     pub unit_mult: i32,
-    /// The `data_value` field represents the value of the datum.
-    pub data_value: i64,
+    /// The `data_value` field represents the value of the datum, or the reason it is missing.
+    pub data_value: BeaValue,
 }
 
 impl BeaDatum {
+    /// The `table` method returns the BEA table of the datum, read from the line code.
+    pub fn table(&self) -> Clean<BeaTable> {
+        Ok(BeaTable::parse_code(&self.code)?.0)
+    }
+
+    /// The `line_code` method returns the line number of the datum within its table, read from
+    /// the line code.
+    pub fn line_code(&self) -> Clean<u32> {
+        Ok(BeaTable::parse_code(&self.code)?.1)
+    }
+
     /// The `code` field represents the BEA table code.  This function returns the cloned value of
     /// the field.
     pub fn code(&self) -> String {
@@ -179,8 +197,22 @@ impl BeaDatum {
 
     /// The `data_value` field represents the value of the datum.  This function returns the value
     /// of the field.
-    pub fn data_value(&self) -> i64 {
+    pub fn data_value(&self) -> BeaValue {
+        self.data_value.clone()
+    }
+
+    /// The `measure` method returns the typed unit of measure of the datum.
+    pub fn measure(&self) -> BeaMeasure {
+        BeaMeasure::new(&self.cl_unit, &self.description)
+    }
+
+    /// The `value` method returns the value of the datum in whole units of its
+    /// [`BeaMeasure`], multiplying `data_value` by ten to the power of `unit_mult`, so that a
+    /// value of 5 in "Thousands of dollars" returns 5000.  Returns `None` if the value is missing.
+    pub fn value(&self) -> Option<f64> {
         self.data_value
+            .number()
+            .map(|v| v as f64 * 10f64.powi(self.unit_mult))
     }
 
     /// The `names` method returns the column names identified in [`BeaColumns`] for use as headers
@@ -236,6 +268,19 @@ impl BeaData {
         Ok(BeaData(records))
     }
 
+    /// This method loads the processed CSV of each table in `tables` into a single `BeaData`.  The
+    /// path to each CSV is read from the environmental variable named by [`BeaTable::csv_var`],
+    /// e.g. "BEA_CAINC1_CSV".
+    pub fn from_tables(tables: &[BeaTable]) -> Clean<Self> {
+        let mut records = Vec::new();
+        for table in tables {
+            let path = std::env::var(table.csv_var())?;
+            info!("Reading {} from {}.", table, &path);
+            records.extend(Self::from_csv(path)?.0);
+        }
+        Ok(BeaData(records))
+    }
+
     /// This method writes the vector of type [`BeaDatum`] in the `records` field of `BeaData` to a
     /// CSV file at location `title`.  Each element in the vector will become a row in the
     /// spreadsheet.
@@ -312,6 +357,49 @@ impl BeaData {
         tree
     }
 
+    /// This function returns the unique tables in the `records` vector.  Records with a line code
+    /// that does not parse are skipped.
+    pub fn tables(&self) -> Vec<BeaTable> {
+        let mut keys = self
+            .iter()
+            .filter_map(|r| r.table().ok())
+            .collect::<Vec<BeaTable>>();
+        keys.sort();
+        keys.dedup();
+        keys
+    }
+
+    /// This function returns the records from `table`.
+    pub fn table(&self, table: &BeaTable) -> Self {
+        let records = self
+            .iter()
+            .filter(|r| r.table().ok().as_ref() == Some(table))
+            .cloned()
+            .collect::<Vec<BeaDatum>>();
+        Self(records)
+    }
+
+    /// This function returns the lines of `table` present in the records, a BTreeMap of line
+    /// numbers and description values, sorted by line number.  Lines in the catalog of the table
+    /// from [`BeaTable::catalog`] take the catalog description, and other lines take the
+    /// description of the first record.
+    pub fn line_codes(&self, table: &BeaTable) -> BTreeMap<u32, String> {
+        let catalog = table.catalog();
+        let mut tree = BTreeMap::new();
+        for record in self.iter() {
+            if let Ok((key, line)) = BeaTable::parse_code(&record.code) {
+                if &key == table {
+                    tree.entry(line)
+                        .or_insert_with(|| match catalog.get(&line) {
+                            Some(description) => description.clone(),
+                            None => record.description(),
+                        });
+                }
+            }
+        }
+        tree
+    }
+
     /// This functions returns unique FIPS numbers from the `records` vector.
     pub fn geofips_keys(&self) -> Vec<i32> {
         let mut keys = self.iter().map(|r| r.geo_fips()).collect::<Vec<i32>>();
@@ -348,7 +436,7 @@ impl BeaData {
         keys
     }
 
-    /// Filters records in the struct based by comparing the string representation of values in the field specified in `filter` against the `test` value.  The `filter` field can take the values "year", "code", and "fips".
+    /// Filters records in the struct based by comparing the string representation of values in the field specified in `filter` against the `test` value.  The `filter` field can take the values "year", "code", "table", "fips", "location" and "description".  A "fips" value matches the FIPS number exactly, so "00000" selects the nation.  To select areas by name or state, use [`BeaData::filter_areas`].
     pub fn filter(&self, filter: &str, test: &str) -> Self {
         trace!("Calling filter on {} records.", self.len());
         let mut records = Vec::new();
@@ -375,10 +463,21 @@ impl BeaData {
             }
             "fips" => {
                 tracing::trace!("Filtering by fips {}", test);
+                let fips = test.trim().parse::<i32>().ok();
+                records.append(
+                    &mut self
+                        .iter()
+                        .filter(|d| fips == Some(d.geo_fips()))
+                        .cloned()
+                        .collect::<Vec<BeaDatum>>(),
+                )
+            }
+            "table" => {
+                tracing::trace!("Filtering by table {}", test);
                 records.append(
                     &mut self
                         .iter()
-                        .filter(|d| format!("{}", d.geo_fips()).as_str() == test)
+                        .filter(|d| d.table().map(|t| t.name() == test).unwrap_or(false))
                         .cloned()
                         .collect::<Vec<BeaDatum>>(),
                 )
@@ -408,7 +507,7 @@ impl BeaData {
         Self(records)
     }
 
-    /// Filters records in the struct based by comparing the string representation of values in the field specified in `filter` against the values in the `test` parameter.  The `filter` field can take the values "year", "code", and "fips".
+    /// Filters records in the struct based by comparing the string representation of values in the field specified in `filter` against the values in the `test` parameter.  The `filter` field can take the values "year", "code", "table", "fips", "location" and "description".  A "fips" value matches the FIPS number exactly, as in [`BeaData::filter`].
     pub fn filter_many(&self, filter: &str, test: &[String]) -> Self {
         trace!("Calling filter on {} records.", self.len());
         let mut records = Vec::new();
@@ -435,10 +534,28 @@ impl BeaData {
             }
             "fips" => {
                 tracing::trace!("Filtering by fips {:?}", test);
+                let fips = test
+                    .iter()
+                    .filter_map(|v| v.trim().parse::<i32>().ok())
+                    .collect::<BTreeSet<i32>>();
+                records.append(
+                    &mut self
+                        .iter()
+                        .filter(|d| fips.contains(&d.geo_fips()))
+                        .cloned()
+                        .collect::<Vec<BeaDatum>>(),
+                );
+            }
+            "table" => {
+                tracing::trace!("Filtering by tables {:?}", test);
                 records.append(
                     &mut self
                         .iter()
-                        .filter(|d| test.contains(&format!("{}", d.geo_fips())))
+                        .filter(|d| {
+                            d.table()
+                                .map(|t| test.contains(&t.name().to_string()))
+                                .unwrap_or(false)
+                        })
                         .cloned()
                         .collect::<Vec<BeaDatum>>(),
                 );
@@ -469,6 +586,27 @@ impl BeaData {
         Self(records)
     }
 
+    /// The `filter_areas` method returns the records of the areas selected by `queries`, each
+    /// resolved through `catalog` as described by [`FipsCatalog::resolve`].  With `lineage`, a
+    /// FIPS number also selects the retired codes it replaced and the codes replacing it, as
+    /// described by [`FipsCatalog::lineage`].
+    pub fn filter_areas(&self, catalog: &FipsCatalog, queries: &[String], lineage: bool) -> Self {
+        let mut fips = queries
+            .iter()
+            .flat_map(|v| catalog.resolve(v))
+            .collect::<BTreeSet<i32>>();
+        if lineage {
+            fips = fips.iter().flat_map(|v| catalog.lineage(*v)).collect();
+        }
+        tracing::trace!("Filtering by areas {:?}", fips);
+        Self(
+            self.iter()
+                .filter(|d| fips.contains(&d.geo_fips()))
+                .cloned()
+                .collect(),
+        )
+    }
+
     /// Filters records in the struct based upon string representations of the values for the
     /// "year", "code" and "fips" fields.
     pub fn search(&self, year: &str, code: &str, fips: &str) -> Self {
@@ -483,6 +621,88 @@ impl BeaData {
     pub fn names() -> Vec<String> {
         BeaColumns::names()
     }
+
+    /// This method writes the records to a CSV file at location `path` with values in whole units
+    /// of a normalized `unit` column, as described by [`BeaNormalized`].
+    pub fn to_normalized_csv<P: AsRef<std::path::Path>>(&self, path: P) -> Clean<()> {
+        let mut records = self.iter().map(BeaNormalized::from).collect::<Vec<_>>();
+        to_csv(&mut records, path)?;
+        Ok(())
+    }
+
+    /// The `missing` method returns the number of records with a missing value, keyed by the
+    /// reason given by BEA.
+    pub fn missing(&self) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::new();
+        self.iter()
+            .filter(|v| v.data_value.is_missing())
+            .for_each(|v| *counts.entry(v.data_value.reason().to_string()).or_insert(0) += 1);
+        counts
+    }
+
+    /// The `with_derived` method appends the records of each derived series in `derived`, so
+    /// they can be filtered, pivoted and exported like the records they are computed from.
+    /// Definitions are evaluated in order, so later series may use earlier ones.
+    pub fn with_derived(mut self, derived: &[BeaDerived]) -> Clean<Self> {
+        for series in derived {
+            let records = series.evaluate(&self)?;
+            info!("Derived {} records for {}.", records.len(), series.code);
+            self.extend(records);
+        }
+        Ok(self)
+    }
+
+    /// The `with_regions` method appends the records of each region in `regions`, as described
+    /// by [`BeaRegions::aggregate`].
+    pub fn with_regions(mut self, regions: &BeaRegions, missing: RegionMissing) -> Clean<Self> {
+        let records = regions.aggregate(&self, missing)?;
+        info!("Aggregated {} region records.", records.len());
+        self.extend(records.0);
+        Ok(self)
+    }
+
+    /// The `pivot` method returns the records as a [`BeaPivot`], with one series of values by
+    /// year for each pair of FIPS number and line code.
+    pub fn pivot(&self) -> BeaPivot {
+        BeaPivot::from(self)
+    }
+}
+
+/// The `BeaNormalized` struct holds a [`BeaDatum`] for export, with the unit multiplier applied
+/// to the value and the unit normalized to a [`BeaMeasure`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd)]
+pub struct BeaNormalized {
+    /// The BEA line code.
+    pub code: String,
+    /// The FIPS number of the datum.
+    pub geo_fips: i32,
+    /// The FIPS description of the datum.
+    pub geo_name: String,
+    /// The year of the datum.
+    pub time_period: i32,
+    /// The description of the data value.
+    pub description: String,
+    /// The normalized unit of measure.
+    pub unit: String,
+    /// The value of the datum in whole units, if not missing.
+    pub value: Option<f64>,
+    /// The BEA code attached to the value, e.g. "(D)" or "E".
+    pub note: String,
+}
+
+impl From<&BeaDatum> for BeaNormalized {
+    fn from(datum: &BeaDatum) -> Self {
+        Self {
+            code: datum.code.clone(),
+            geo_fips: datum.geo_fips,
+            geo_name: datum.geo_name.clone(),
+            time_period: datum.time_period,
+            description: datum.description.clone(),
+            unit: datum.measure().to_string(),
+            value: datum.value(),
+            note: datum.data_value.code().to_string(),
+        }
+    }
 }
 
 impl TryFrom<BeaDataRaw> for BeaData {
@@ -496,33 +716,56 @@ impl TryFrom<BeaDataRaw> for BeaData {
         let bar = ProgressBar::new(raw.len() as u64);
         bar.set_style(style);
         let mut res = Vec::new();
-        let mut k = 0;
+        let mut missing = 0;
+        let mut catalogs = HashMap::new();
+        let mut uncataloged = BTreeSet::new();
         for (i, record) in raw.iter().cloned().enumerate() {
             trace!("Processing row {}", i);
-            let value = str_to_int(&record.data_value)?;
-            if let Some(num) = value {
-                res.push(BeaDatum {
-                    code: record.code,
-                    geo_fips: record.geo_fips,
-                    geo_name: record.geo_name,
-                    time_period: record.time_period,
-                    description: record.description,
-                    cl_unit: record.cl_unit,
-                    unit_mult: record.unit_mult,
-                    data_value: num,
-                });
-            } else {
+            if let Ok((table, line)) = BeaTable::parse_code(&record.code) {
+                let catalog = catalogs
+                    .entry(table.clone())
+                    .or_insert_with(|| table.catalog());
+                if !catalog.is_empty() && !catalog.contains_key(&line) {
+                    uncataloged.insert(record.code.clone());
+                }
+            }
+            let data_value = match record.data_value.parse::<BeaValue>() {
+                Ok(value) => value,
+                Err(_) => {
+                    warn!("Unrecognized BEA value: {}", record.data_value);
+                    BeaValue::Other(record.data_value.trim().to_string())
+                }
+            };
+            if data_value.is_missing() {
                 trace!(
-                    "Dropped record {:?}, fips {}, year {}: NaN",
+                    "Record {:?}, fips {}, year {}: {}",
                     record.code,
                     record.geo_fips,
-                    record.time_period
+                    record.time_period,
+                    data_value.reason()
                 );
-                k += 1;
+                missing += 1;
             }
+            res.push(BeaDatum {
+                code: record.code,
+                geo_fips: record.geo_fips,
+                geo_name: record.geo_name,
+                time_period: record.time_period,
+                description: record.description,
+                cl_unit: record.cl_unit,
+                unit_mult: record.unit_mult,
+                data_value,
+            });
             bar.inc(1);
         }
-        info!("Dropped {} records with NA values.", k);
+        info!("{} records with missing values.", missing);
+        if !uncataloged.is_empty() {
+            info!(
+                "{} line codes not in the table catalogs: {:?}",
+                uncataloged.len(),
+                uncataloged
+            );
+        }
         Ok(BeaData(res))
     }
 }
@@ -533,47 +776,3 @@ impl From<Vec<BeaDatum>> for BeaData {
         Self(records)
     }
 }
-
-/// This functions removes commas and note tags (trailing 'E's) from BEA values.  Called by
-/// ['str_to_int'].
-// fn remove_comma<'a, 'b>(value: &'a str, num: Option<String>) -> IResult<&'a str, Option<String>> {
-fn remove_comma(value: &str, num: Option<String>) -> IResult<&str, Option<String>> {
-    let mut res = "".to_string();
-    let mut out = None;
-    if let Some(val) = num {
-        res.push_str(&val);
-    }
-    let val = value.chars().next();
-    if let Some(chr) = val {
-        if is_digit(chr as u8) {
-            let (mut rem, val) = digit1(value)?;
-            res.push_str(val);
-            if !rem.is_empty() {
-                (rem, _) = alt((tag(","), tag(" E")))(rem)?;
-                (rem, out) = remove_comma(rem, Some(res))?;
-                Ok((rem, out))
-            } else {
-                Ok((rem, Some(res)))
-            }
-        } else {
-            Ok(("", out))
-        }
-    } else {
-        Ok(("", out))
-    }
-}
-
-/// This function converts the string representation of a number where thousands are separated by
-/// commas into an integer type.  Calls ['remove_comma'].  Called by [`BeaData::try_from()`].
-fn str_to_int(value: &str) -> Clean<Option<i64>> {
-    let stub = "".to_string();
-    if let Ok((_, res)) = remove_comma(value, Some(stub)) {
-        if let Some(val) = res {
-            Ok(Some(val.parse::<i64>()?))
-        } else {
-            Ok(None)
-        }
-    } else {
-        Err(Bandage::Parse)
-    }
-}
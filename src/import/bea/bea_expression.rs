@@ -0,0 +1,256 @@
+use crate::import::bea::{BeaData, BeaDatum, BeaMeasure, BeaValue};
+use aid::prelude::*;
+use nom::branch::alt;
+use nom::character::complete::{alpha1, alphanumeric0, char, digit1, one_of, space0};
+use nom::combinator::{map, recognize};
+use nom::multi::fold_many0;
+use nom::number::complete::double;
+use nom::sequence::{delimited, pair, tuple};
+use nom::IResult;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// The number of significant digits kept by [`scale`].
+const SIGNIFICANT_DIGITS: i32 = 12;
+
+/// The `BeaExpression` enum holds an arithmetic expression over BEA line codes, such as
+/// "CAINC5N-10 / CAEMP25N-10".
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BeaExpression {
+    /// The value of a line code.
+    Code(String),
+    /// A constant, such as 100 to convert a share to a percentage.
+    Number(f64),
+    /// The sum of two expressions.
+    Add(Box<BeaExpression>, Box<BeaExpression>),
+    /// The difference of two expressions.
+    Subtract(Box<BeaExpression>, Box<BeaExpression>),
+    /// The product of two expressions.
+    Multiply(Box<BeaExpression>, Box<BeaExpression>),
+    /// The quotient of two expressions.
+    Divide(Box<BeaExpression>, Box<BeaExpression>),
+}
+
+impl BeaExpression {
+    /// The `codes` method returns the line codes used in the expression.
+    pub fn codes(&self) -> BTreeSet<String> {
+        let mut codes = BTreeSet::new();
+        self.collect_codes(&mut codes);
+        codes
+    }
+
+    fn collect_codes(&self, codes: &mut BTreeSet<String>) {
+        match self {
+            Self::Code(code) => {
+                codes.insert(code.clone());
+            }
+            Self::Number(_) => {}
+            Self::Add(a, b) | Self::Subtract(a, b) | Self::Multiply(a, b) | Self::Divide(a, b) => {
+                a.collect_codes(codes);
+                b.collect_codes(codes);
+            }
+        }
+    }
+
+    /// The `measure` method returns the unit of measure of the expression, given the measure of
+    /// each line code in `measures`.  Returns an error if a line code has no measure, or if the
+    /// expression adds or subtracts values in different units.
+    pub fn measure(&self, measures: &HashMap<String, BeaMeasure>) -> Clean<BeaMeasure> {
+        match self {
+            Self::Code(code) => measures
+                .get(code)
+                .cloned()
+                .ok_or_else(|| Bandage::Hint(format!("No records for {}.", code))),
+            Self::Number(_) => Ok(BeaMeasure::Ratio),
+            Self::Add(a, b) | Self::Subtract(a, b) => {
+                let measure = a.measure(measures)?;
+                measure.check(&b.measure(measures)?)?;
+                Ok(measure)
+            }
+            Self::Multiply(a, b) => Ok(a.measure(measures)?.multiply(&b.measure(measures)?)),
+            Self::Divide(a, b) => Ok(a.measure(measures)?.divide(&b.measure(measures)?)),
+        }
+    }
+
+    /// The `evaluate` method returns the value of the expression given the value of each line
+    /// code from `lookup`, which returns the missing [`BeaValue`] when a value is missing.  A
+    /// missing operand makes the result missing for the same reason, and division by zero is not
+    /// meaningful.
+    pub fn evaluate<F: Fn(&str) -> Result<f64, BeaValue>>(
+        &self,
+        lookup: &F,
+    ) -> Result<f64, BeaValue> {
+        match self {
+            Self::Code(code) => lookup(code),
+            Self::Number(value) => Ok(*value),
+            Self::Add(a, b) => Ok(a.evaluate(lookup)? + b.evaluate(lookup)?),
+            Self::Subtract(a, b) => Ok(a.evaluate(lookup)? - b.evaluate(lookup)?),
+            Self::Multiply(a, b) => Ok(a.evaluate(lookup)? * b.evaluate(lookup)?),
+            Self::Divide(a, b) => {
+                let numerator = a.evaluate(lookup)?;
+                let denominator = b.evaluate(lookup)?;
+                if denominator == 0. {
+                    Err(BeaValue::NotMeaningful)
+                } else {
+                    Ok(numerator / denominator)
+                }
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for BeaExpression {
+    type Err = Bandage;
+
+    /// Reads an expression of line codes and numbers joined by "+", "-", "*" and "/", with
+    /// parentheses for grouping, e.g. "(CAINC5N-50 + CAINC5N-60) / CAINC5N-35".
+    fn from_str(input: &str) -> Clean<Self> {
+        match parse_expression(input.trim()) {
+            Ok(("", expression)) => Ok(expression),
+            _ => Err(Bandage::Hint(format!("Invalid BEA expression: {}", input))),
+        }
+    }
+}
+
+/// Parses a sum or difference of terms.  Called by [`BeaExpression::from_str`].
+fn parse_expression(input: &str) -> IResult<&str, BeaExpression> {
+    let (rem, first) = parse_term(input)?;
+    fold_many0(
+        pair(delimited(space0, one_of("+-"), space0), parse_term),
+        move || first.clone(),
+        |a, (op, b)| match op {
+            '+' => BeaExpression::Add(Box::new(a), Box::new(b)),
+            _ => BeaExpression::Subtract(Box::new(a), Box::new(b)),
+        },
+    )(rem)
+}
+
+/// Parses a product or quotient of factors.  Called by [`parse_expression`].
+fn parse_term(input: &str) -> IResult<&str, BeaExpression> {
+    let (rem, first) = parse_factor(input)?;
+    fold_many0(
+        pair(delimited(space0, one_of("*/"), space0), parse_factor),
+        move || first.clone(),
+        |a, (op, b)| match op {
+            '*' => BeaExpression::Multiply(Box::new(a), Box::new(b)),
+            _ => BeaExpression::Divide(Box::new(a), Box::new(b)),
+        },
+    )(rem)
+}
+
+/// Parses a line code, a number or an expression in parentheses.  Called by [`parse_term`].
+fn parse_factor(input: &str) -> IResult<&str, BeaExpression> {
+    delimited(
+        space0,
+        alt((
+            delimited(char('('), parse_expression, pair(space0, char(')'))),
+            map(
+                recognize(tuple((alpha1, alphanumeric0, char('-'), digit1))),
+                |v: &str| BeaExpression::Code(v.to_uppercase()),
+            ),
+            map(double, BeaExpression::Number),
+        )),
+        space0,
+    )(input)
+}
+
+/// The `BeaDerived` struct defines a derived series, stored under the line code `code`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BeaDerived {
+    /// The line code of the derived series, e.g. "DERIVED-1".
+    pub code: String,
+    /// The description of the derived series.
+    pub description: String,
+    /// The expression computing the series.
+    pub expression: BeaExpression,
+}
+
+impl BeaDerived {
+    /// Creates a new `BeaDerived` named `code` with `description`, computed by `expression`.
+    pub fn new(code: &str, description: &str, expression: &str) -> Clean<Self> {
+        Ok(Self {
+            code: code.trim().to_string(),
+            description: description.trim().to_string(),
+            expression: expression.parse()?,
+        })
+    }
+
+    /// The `evaluate` method computes the derived series for each location and year with a
+    /// record of any line code in the expression, returning one [`BeaDatum`] for each.  Values
+    /// are rounded to the fixed precision of the measure given by [`BeaMeasure::unit_mult`], so
+    /// every record of the series shares one `unit_mult`.
+    pub fn evaluate(&self, data: &BeaData) -> Clean<Vec<BeaDatum>> {
+        let codes = self.expression.codes();
+        let mut measures = HashMap::new();
+        let mut values = HashMap::new();
+        let mut keys = BTreeMap::new();
+        for record in data.iter().filter(|v| codes.contains(&v.code)) {
+            measures
+                .entry(record.code.clone())
+                .or_insert_with(|| record.measure());
+            keys.entry((record.geo_fips, record.time_period))
+                .or_insert_with(|| record.geo_name.clone());
+            values.insert(
+                (record.geo_fips, record.time_period, record.code.clone()),
+                record,
+            );
+        }
+        let measure = self.expression.measure(&measures)?;
+        let unit_mult = measure.unit_mult();
+        let mut records = Vec::new();
+        for ((fips, year), name) in keys {
+            let lookup = |code: &str| match values.get(&(fips, year, code.to_string())) {
+                Some(record) => record.value().ok_or_else(|| record.data_value.clone()),
+                None => Err(BeaValue::NotAvailable),
+            };
+            let data_value = match self.expression.evaluate(&lookup) {
+                Ok(value) if value.is_finite() => {
+                    BeaValue::Number((value / 10f64.powi(unit_mult)).round() as i64)
+                }
+                Ok(_) => BeaValue::NotMeaningful,
+                Err(value) => value,
+            };
+            records.push(BeaDatum {
+                code: self.code.clone(),
+                geo_fips: fips,
+                geo_name: name,
+                time_period: year,
+                description: self.description.clone(),
+                cl_unit: measure.to_string(),
+                unit_mult,
+                data_value,
+            });
+        }
+        Ok(records)
+    }
+}
+
+impl std::str::FromStr for BeaDerived {
+    type Err = Bandage;
+
+    /// Reads a definition of the form "CODE = EXPRESSION", using the expression as the
+    /// description, e.g. "DERIVED-1 = CAINC5N-10 / CAEMP25N-10".
+    fn from_str(input: &str) -> Clean<Self> {
+        match input.split_once('=') {
+            Some((code, expression)) => Self::new(code, expression, expression),
+            None => Err(Bandage::Hint(format!(
+                "Expected CODE = EXPRESSION, found {}",
+                input
+            ))),
+        }
+    }
+}
+
+/// Splits `value` into an integer of at most [`SIGNIFICANT_DIGITS`] digits and a power of ten.
+pub(crate) fn scale(value: f64) -> (BeaValue, i32) {
+    if value == 0. {
+        return (BeaValue::Number(0), 0);
+    }
+    let mut exponent = value.abs().log10().floor() as i32 - (SIGNIFICANT_DIGITS - 1);
+    let mut number = (value / 10f64.powi(exponent)).round() as i64;
+    while number % 10 == 0 {
+        number /= 10;
+        exponent += 1;
+    }
+    (BeaValue::Number(number), exponent)
+}
@@ -0,0 +1,467 @@
+use crate::import::bea::{BeaData, REGION_FIPS};
+use aid::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The states and the District of Columbia, as the state FIPS number, postal abbreviation and
+/// name.
+const STATES: &[(i32, &str, &str)] = &[
+    (1, "AL", "Alabama"),
+    (2, "AK", "Alaska"),
+    (4, "AZ", "Arizona"),
+    (5, "AR", "Arkansas"),
+    (6, "CA", "California"),
+    (8, "CO", "Colorado"),
+    (9, "CT", "Connecticut"),
+    (10, "DE", "Delaware"),
+    (11, "DC", "District of Columbia"),
+    (12, "FL", "Florida"),
+    (13, "GA", "Georgia"),
+    (15, "HI", "Hawaii"),
+    (16, "ID", "Idaho"),
+    (17, "IL", "Illinois"),
+    (18, "IN", "Indiana"),
+    (19, "IA", "Iowa"),
+    (20, "KS", "Kansas"),
+    (21, "KY", "Kentucky"),
+    (22, "LA", "Louisiana"),
+    (23, "ME", "Maine"),
+    (24, "MD", "Maryland"),
+    (25, "MA", "Massachusetts"),
+    (26, "MI", "Michigan"),
+    (27, "MN", "Minnesota"),
+    (28, "MS", "Mississippi"),
+    (29, "MO", "Missouri"),
+    (30, "MT", "Montana"),
+    (31, "NE", "Nebraska"),
+    (32, "NV", "Nevada"),
+    (33, "NH", "New Hampshire"),
+    (34, "NJ", "New Jersey"),
+    (35, "NM", "New Mexico"),
+    (36, "NY", "New York"),
+    (37, "NC", "North Carolina"),
+    (38, "ND", "North Dakota"),
+    (39, "OH", "Ohio"),
+    (40, "OK", "Oklahoma"),
+    (41, "OR", "Oregon"),
+    (42, "PA", "Pennsylvania"),
+    (44, "RI", "Rhode Island"),
+    (45, "SC", "South Carolina"),
+    (46, "SD", "South Dakota"),
+    (47, "TN", "Tennessee"),
+    (48, "TX", "Texas"),
+    (49, "UT", "Utah"),
+    (50, "VT", "Vermont"),
+    (51, "VA", "Virginia"),
+    (53, "WA", "Washington"),
+    (54, "WV", "West Virginia"),
+    (55, "WI", "Wisconsin"),
+    (56, "WY", "Wyoming"),
+];
+
+/// The BEA regions, as the FIPS number and name.
+const BEA_REGIONS: &[(i32, &str)] = &[
+    (91000, "New England"),
+    (92000, "Mideast"),
+    (93000, "Great Lakes"),
+    (94000, "Plains"),
+    (95000, "Southeast"),
+    (96000, "Southwest"),
+    (97000, "Rocky Mountain"),
+    (98000, "Far West"),
+];
+
+/// The counties and metropolitan areas of Oregon, as the FIPS number and name used by BEA.
+/// Areas elsewhere are read from BEA data or from a reference file with
+/// [`FipsCatalog::from_csv`].
+const OREGON_AREAS: &[(i32, &str)] = &[
+    (41001, "Baker, OR"),
+    (41003, "Benton, OR"),
+    (41005, "Clackamas, OR"),
+    (41007, "Clatsop, OR"),
+    (41009, "Columbia, OR"),
+    (41011, "Coos, OR"),
+    (41013, "Crook, OR"),
+    (41015, "Curry, OR"),
+    (41017, "Deschutes, OR"),
+    (41019, "Douglas, OR"),
+    (41021, "Gilliam, OR"),
+    (41023, "Grant, OR"),
+    (41025, "Harney, OR"),
+    (41027, "Hood River, OR"),
+    (41029, "Jackson, OR"),
+    (41031, "Jefferson, OR"),
+    (41033, "Josephine, OR"),
+    (41035, "Klamath, OR"),
+    (41037, "Lake, OR"),
+    (41039, "Lane, OR"),
+    (41041, "Lincoln, OR"),
+    (41043, "Linn, OR"),
+    (41045, "Malheur, OR"),
+    (41047, "Marion, OR"),
+    (41049, "Morrow, OR"),
+    (41051, "Multnomah, OR"),
+    (41053, "Polk, OR"),
+    (41055, "Sherman, OR"),
+    (41057, "Tillamook, OR"),
+    (41059, "Umatilla, OR"),
+    (41061, "Union, OR"),
+    (41063, "Wallowa, OR"),
+    (41065, "Wasco, OR"),
+    (41067, "Washington, OR"),
+    (41069, "Wheeler, OR"),
+    (41071, "Yamhill, OR"),
+    (10540, "Albany-Lebanon, OR (Metropolitan Statistical Area)"),
+    (13460, "Bend, OR (Metropolitan Statistical Area)"),
+    (18700, "Corvallis, OR (Metropolitan Statistical Area)"),
+    (
+        21660,
+        "Eugene-Springfield, OR (Metropolitan Statistical Area)",
+    ),
+    (24420, "Grants Pass, OR (Metropolitan Statistical Area)"),
+    (32780, "Medford, OR (Metropolitan Statistical Area)"),
+    (
+        38900,
+        "Portland-Vancouver-Hillsboro, OR-WA (Metropolitan Statistical Area)",
+    ),
+    (41420, "Salem, OR (Metropolitan Statistical Area)"),
+];
+
+/// County codes retired since 1990, as the retired FIPS number, its name, the FIPS number of the
+/// area replacing it if there is a single one, and the first year without the retired code.
+const FIPS_CHANGES: &[(i32, &str, Option<i32>, i32)] = &[
+    (2261, "Valdez-Cordova, AK", None, 2020),
+    (2270, "Wade Hampton, AK", Some(2158), 2015),
+    (9001, "Fairfield, CT", None, 2022),
+    (9003, "Hartford, CT", None, 2022),
+    (9005, "Litchfield, CT", None, 2022),
+    (9007, "Middlesex, CT", None, 2022),
+    (9009, "New Haven, CT", None, 2022),
+    (9011, "New London, CT", None, 2022),
+    (9013, "Tolland, CT", None, 2022),
+    (9015, "Windham, CT", None, 2022),
+    (12025, "Dade, FL", Some(12086), 1997),
+    (46113, "Shannon, SD", Some(46102), 2015),
+    (51515, "Bedford city, VA", Some(51019), 2013),
+    (51560, "Clifton Forge city, VA", Some(51005), 2001),
+    (51780, "South Boston city, VA", Some(51083), 1995),
+];
+
+/// The `FipsKind` enum is the kind of area identified by a FIPS number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum FipsKind {
+    /// The United States.
+    Nation,
+    /// A BEA region, such as the Far West.
+    Region,
+    /// A state or the District of Columbia.
+    State,
+    /// A county or county equivalent.
+    County,
+    /// A BEA combination area, such as a Virginia independent city combined with its county.
+    Combination,
+    /// A metropolitan or micropolitan statistical area.
+    Metro,
+    /// A custom region, such as a [`crate::import::BeaRegion`].
+    Custom,
+    /// Any other area.
+    Other,
+}
+
+/// The `FipsArea` struct holds one entry of a [`FipsCatalog`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FipsArea {
+    /// The FIPS number of the area.
+    pub fips: i32,
+    /// The name of the area, e.g. "Josephine, OR".
+    pub name: String,
+    /// The kind of area.
+    pub kind: FipsKind,
+    /// The FIPS number of the area containing this one, such as the state of a county.
+    pub parent: Option<i32>,
+    /// The FIPS number of the area replacing this one, if the code is retired.
+    pub replaced_by: Option<i32>,
+    /// The first year without this code, if the code is retired.
+    pub retired: Option<i32>,
+}
+
+impl FipsArea {
+    /// Creates a new `FipsArea` from the FIPS number and name, inferring the kind and parent.
+    pub fn new(fips: i32, name: &str) -> Self {
+        let name = name.trim().to_string();
+        let state = fips / 1000;
+        let county = fips % 1000;
+        let is_state = STATES.iter().any(|v| v.0 == state);
+        let kind = if fips == 0 {
+            FipsKind::Nation
+        } else if BEA_REGIONS.iter().any(|v| v.0 == fips) {
+            FipsKind::Region
+        } else if fips > REGION_FIPS && fips < REGION_FIPS + 1000 {
+            FipsKind::Custom
+        } else if name.contains("Metropolitan") || name.contains("Micropolitan") {
+            FipsKind::Metro
+        } else if is_state && county == 0 {
+            FipsKind::State
+        } else if is_state && (county >= 900 || name.contains(" + ")) {
+            FipsKind::Combination
+        } else if is_state {
+            FipsKind::County
+        } else {
+            FipsKind::Other
+        };
+        let parent = match kind {
+            FipsKind::Region | FipsKind::State => Some(0),
+            FipsKind::County | FipsKind::Combination => Some(state * 1000),
+            FipsKind::Metro => Self::metro_state(&name),
+            _ => None,
+        };
+        Self {
+            fips,
+            name,
+            kind,
+            parent,
+            replaced_by: None,
+            retired: None,
+        }
+    }
+
+    /// Returns the FIPS number of the first state named in a metropolitan area name, e.g. Oregon
+    /// for "Portland-Vancouver-Hillsboro, OR-WA (Metropolitan Statistical Area)".
+    fn metro_state(name: &str) -> Option<i32> {
+        let (_, states) = name.split(" (").next()?.rsplit_once(", ")?;
+        FipsCatalog::state(states.split('-').next()?)
+    }
+
+    /// The `code` method returns the FIPS number as the five-digit code, e.g. "01001".
+    pub fn code(&self) -> String {
+        format!("{:05}", self.fips)
+    }
+}
+
+/// The `FipsCatalog` struct holds reference entries for FIPS numbers, keyed by FIPS number.  The
+/// catalog includes the nation, the states, the BEA regions, the counties and metropolitan areas
+/// of Oregon and the county codes retired since 1990, and any other areas read from a reference
+/// file or from BEA data.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FipsCatalog(BTreeMap<i32, FipsArea>);
+
+impl Default for FipsCatalog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FipsCatalog {
+    /// Creates a new `FipsCatalog` of the nation, states, BEA regions, Oregon areas and retired
+    /// county codes.
+    pub fn new() -> Self {
+        let mut catalog = Self(BTreeMap::new());
+        catalog.insert(FipsArea::new(0, "United States"));
+        for (state, _, name) in STATES {
+            catalog.insert(FipsArea::new(state * 1000, name));
+        }
+        for (fips, name) in BEA_REGIONS.iter().chain(OREGON_AREAS) {
+            catalog.insert(FipsArea::new(*fips, name));
+        }
+        for (fips, name, replaced_by, retired) in FIPS_CHANGES {
+            let mut area = FipsArea::new(*fips, name);
+            area.replaced_by = *replaced_by;
+            area.retired = Some(*retired);
+            catalog.insert(area);
+        }
+        catalog
+    }
+
+    /// Adds `area` to the catalog, keeping the retirement of a known retired code.
+    pub fn insert(&mut self, mut area: FipsArea) {
+        if let Some(known) = self.0.get(&area.fips) {
+            area.replaced_by = area.replaced_by.or(known.replaced_by);
+            area.retired = area.retired.or(known.retired);
+        }
+        self.0.insert(area.fips, area);
+    }
+
+    /// The `get` method returns the entry for `fips`, if any.
+    pub fn get(&self, fips: i32) -> Option<&FipsArea> {
+        self.0.get(&fips)
+    }
+
+    /// The `areas` method returns the entries of the catalog, sorted by FIPS number.
+    pub fn areas(&self) -> Vec<&FipsArea> {
+        self.0.values().collect()
+    }
+
+    /// The `children` method returns the FIPS numbers of the areas with `fips` as parent.
+    pub fn children(&self, fips: i32) -> Vec<i32> {
+        self.0
+            .values()
+            .filter(|v| v.parent == Some(fips))
+            .map(|v| v.fips)
+            .collect()
+    }
+
+    /// The `current` method returns the FIPS number currently in use for `fips`, following
+    /// replacements of retired codes.
+    pub fn current(&self, fips: i32) -> i32 {
+        let mut current = fips;
+        while let Some(next) = self.get(current).and_then(|v| v.replaced_by) {
+            current = next;
+        }
+        current
+    }
+
+    /// The `lineage` method returns `fips` with the codes it replaced and the codes replacing it.
+    pub fn lineage(&self, fips: i32) -> BTreeSet<i32> {
+        let current = self.current(fips);
+        self.0
+            .keys()
+            .filter(|v| self.current(**v) == current)
+            .copied()
+            .chain([fips, current])
+            .collect()
+    }
+
+    /// The `state` method returns the FIPS number of the state named by its postal abbreviation
+    /// or name, ignoring case, e.g. "OR" or "Oregon".
+    pub fn state(query: &str) -> Option<i32> {
+        let query = query.trim().to_lowercase();
+        STATES
+            .iter()
+            .find(|v| v.1.to_lowercase() == query || v.2.to_lowercase() == query)
+            .map(|v| v.0 * 1000)
+    }
+
+    /// The `find` method returns the FIPS numbers of the areas named `name`, ignoring case.  A
+    /// name without a state, such as "Josephine", matches the part of the name before the comma.
+    pub fn find(&self, name: &str) -> Vec<i32> {
+        let name = name.trim().to_lowercase();
+        self.0
+            .values()
+            .filter(|v| {
+                let area = v.name.to_lowercase();
+                area == name || area.split(',').next().map(|s| s.trim()) == Some(name.as_str())
+            })
+            .map(|v| v.fips)
+            .collect()
+    }
+
+    /// The `resolve` method returns the FIPS numbers selected by `query`, which may be:
+    /// * a FIPS number, e.g. "41033" or "00000", matched exactly,
+    /// * a code prefix ending in "*", e.g. "41*" for Oregon and its counties, matching states,
+    ///   counties and combination areas but not metropolitan areas with a similar code,
+    /// * a state abbreviation or name, e.g. "OR" or "Oregon", for the state and its counties and
+    ///   combination areas,
+    /// * an area name, e.g. "Josephine, OR" or "Josephine".
+    pub fn resolve(&self, query: &str) -> BTreeSet<i32> {
+        let query = query.trim();
+        if let Ok(fips) = query.parse::<i32>() {
+            return BTreeSet::from([fips]);
+        }
+        if let Some(prefix) = query.strip_suffix('*') {
+            return self
+                .0
+                .values()
+                .filter(|v| {
+                    matches!(
+                        v.kind,
+                        FipsKind::State | FipsKind::County | FipsKind::Combination
+                    )
+                })
+                .filter(|v| v.code().starts_with(prefix))
+                .map(|v| v.fips)
+                .collect();
+        }
+        if let Some(state) = Self::state(query) {
+            let mut fips = self
+                .children(state)
+                .into_iter()
+                .filter(|v| {
+                    matches!(
+                        self.get(*v).map(|a| a.kind),
+                        Some(FipsKind::County | FipsKind::Combination)
+                    )
+                })
+                .collect::<BTreeSet<i32>>();
+            fips.insert(state);
+            return fips;
+        }
+        self.find(query).into_iter().collect()
+    }
+
+    /// The `resolve_one` method returns the single FIPS number selected by `query`, as described
+    /// by [`FipsCatalog::resolve`].  Returns an error if the query selects no area or several.
+    pub fn resolve_one(&self, query: &str) -> Clean<i32> {
+        let fips = self.resolve(query);
+        match (fips.iter().next(), fips.len()) {
+            (Some(area), 1) => Ok(*area),
+            (None, _) => Err(Bandage::Hint(format!("No area found for {}.", query))),
+            _ => Err(Bandage::Hint(format!(
+                "{} matches several areas: {:?}",
+                query, fips
+            ))),
+        }
+    }
+
+    /// Reads a catalog from a CSV file at `path`, added to the entries of [`FipsCatalog::new`].
+    /// The file holds the columns "fips" and "name", such as a reference list of counties,
+    /// combination areas and metropolitan areas, and may hold the other fields of [`FipsArea`]
+    /// as written by [`FipsCatalog::to_csv`].  Missing kinds and parents are inferred as in
+    /// [`FipsArea::new`].
+    pub fn from_csv<P: AsRef<std::path::Path>>(path: P) -> Clean<Self> {
+        let mut catalog = Self::new();
+        let mut rdr = csv::Reader::from_reader(std::fs::File::open(path)?);
+        for result in rdr.deserialize() {
+            let row: FipsRow = result.map_err(|e| Bandage::Hint(e.to_string()))?;
+            let mut area = FipsArea::new(row.fips, &row.name);
+            area.kind = row.kind.unwrap_or(area.kind);
+            area.parent = row.parent.or(area.parent);
+            area.replaced_by = row.replaced_by;
+            area.retired = row.retired;
+            catalog.insert(area);
+        }
+        Ok(catalog)
+    }
+
+    /// The `with_data` method adds the FIPS numbers and names in `data` to the catalog.  BEA
+    /// marks areas with boundary changes with a trailing "*", which is removed from the name.
+    pub fn with_data(mut self, data: &BeaData) -> Self {
+        for (fips, name) in data.geofips_btree() {
+            self.insert(FipsArea::new(fips, name.trim_end_matches('*')));
+        }
+        self
+    }
+
+    /// Writes the catalog to a CSV file at location `path`.
+    pub fn to_csv<P: AsRef<std::path::Path>>(&self, path: P) -> Clean<()> {
+        let mut wtr = csv::Writer::from_writer(std::fs::File::create(path)?);
+        for area in self.0.values() {
+            wtr.serialize(area)
+                .map_err(|e| Bandage::Hint(e.to_string()))?;
+        }
+        wtr.flush()?;
+        Ok(())
+    }
+}
+
+impl From<&BeaData> for FipsCatalog {
+    /// Adds the FIPS numbers and names in `data` to the entries of [`FipsCatalog::new`], as
+    /// described by [`FipsCatalog::with_data`].
+    fn from(data: &BeaData) -> Self {
+        Self::new().with_data(data)
+    }
+}
+
+/// One row of a FIPS reference file, read by [`FipsCatalog::from_csv`].
+#[derive(Debug, Clone, Deserialize)]
+struct FipsRow {
+    fips: i32,
+    name: String,
+    #[serde(default)]
+    kind: Option<FipsKind>,
+    #[serde(default)]
+    parent: Option<i32>,
+    #[serde(default)]
+    replaced_by: Option<i32>,
+    #[serde(default)]
+    retired: Option<i32>,
+}
@@ -0,0 +1,358 @@
+use crate::import::bea::{BeaData, BeaDatum, BeaTable};
+use crate::prelude::*;
+use aid::prelude::*;
+use derive_more::{Deref, DerefMut};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// The sums published in CAINC5N, as the parent line and its child lines.  Negative child lines
+/// are subtracted from the sum.  Earnings by place of work (line 35) has two breakdowns, by
+/// component and by industry.
+const CAINC5N_SUMS: &[(u32, &[i32])] = &[
+    (10, &[45, 46, 47]),
+    (45, &[35, -36, 42]),
+    (36, &[37, 38]),
+    (35, &[50, 60, 70]),
+    (60, &[61, 62]),
+    (70, &[71, 72]),
+    (35, &[81, 82]),
+    (82, &[90, 2000]),
+    (
+        90,
+        &[
+            100, 200, 300, 400, 500, 600, 700, 800, 900, 1000, 1100, 1200, 1300, 1400, 1500, 1600,
+            1700, 1800, 1900,
+        ],
+    ),
+    (500, &[510, 530]),
+    (2000, &[2001, 2002, 2010]),
+    (2010, &[2011, 2012]),
+];
+
+/// The CAINC5N lines heading a range of subsector lines.  The children of a head without a
+/// published sum are the lines in the data between it and the next head.
+const CAINC5N_HEADS: &[u32] = &[
+    100, 200, 300, 400, 500, 510, 530, 600, 700, 800, 900, 1000, 1100, 1200, 1300, 1400, 1500,
+    1600, 1700, 1800, 1900, 2000,
+];
+
+/// The `BeaSum` struct holds one breakdown of a parent line into child lines.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BeaSum {
+    /// The line number of the parent.
+    pub parent: u32,
+    /// The line numbers of the children, with `true` for lines added and `false` for lines
+    /// subtracted.
+    pub children: Vec<(u32, bool)>,
+}
+
+/// The `BeaLine` struct holds one line of a BEA table and its place in the hierarchy.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BeaLine {
+    /// The BEA line code, e.g. "CAINC5N-500".
+    pub code: String,
+    /// The line number within the table.
+    pub line: u32,
+    /// The line number of the parent, if any.
+    pub parent: Option<u32>,
+    /// The number of ancestors of the line.
+    pub depth: usize,
+    /// The description of the line.
+    pub description: String,
+}
+
+/// The `BeaHierarchy` struct holds the tree of line codes in a BEA table.  The line codes and
+/// descriptions are read from the data, and the sums relating them are known for CAINC5N.  Lines
+/// in other tables have no parent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BeaHierarchy {
+    /// The table of the line codes.
+    pub table: BeaTable,
+    /// The descriptions of the lines present in the data, keyed by line number.
+    pub lines: BTreeMap<u32, String>,
+    /// The sums relating parent lines to child lines.
+    pub sums: Vec<BeaSum>,
+}
+
+impl BeaHierarchy {
+    /// Creates a new `BeaHierarchy` for `table` from the line codes present in `data`.  Sums with
+    /// a parent or child line missing from the data are left out.
+    pub fn new(data: &BeaData, table: &BeaTable) -> Self {
+        let lines = data.line_codes(table);
+        let mut sums = Vec::new();
+        if table == &BeaTable::Cainc5n {
+            for (parent, children) in CAINC5N_SUMS {
+                let children = children
+                    .iter()
+                    .map(|v| (v.unsigned_abs(), *v > 0))
+                    .collect::<Vec<(u32, bool)>>();
+                if lines.contains_key(parent) && children.iter().all(|v| lines.contains_key(&v.0)) {
+                    sums.push(BeaSum {
+                        parent: *parent,
+                        children,
+                    });
+                }
+            }
+            for (i, head) in CAINC5N_HEADS.iter().enumerate() {
+                if !lines.contains_key(head) || sums.iter().any(|v| v.parent == *head) {
+                    continue;
+                }
+                let next = CAINC5N_HEADS.get(i + 1).copied().unwrap_or(u32::MAX);
+                let children = lines
+                    .range(head + 1..next)
+                    .map(|(line, _)| (*line, true))
+                    .collect::<Vec<(u32, bool)>>();
+                if !children.is_empty() {
+                    sums.push(BeaSum {
+                        parent: *head,
+                        children,
+                    });
+                }
+            }
+        }
+        Self {
+            table: table.clone(),
+            lines,
+            sums,
+        }
+    }
+
+    /// The `code` method returns the line code of `line`, e.g. "CAINC5N-500".
+    pub fn code(&self, line: u32) -> String {
+        format!("{}-{}", self.table, line)
+    }
+
+    /// The `codes` method returns the line codes present in the data and their descriptions,
+    /// sorted by line number.
+    pub fn codes(&self) -> Vec<(String, String)> {
+        self.lines
+            .iter()
+            .map(|(line, description)| (self.code(*line), description.clone()))
+            .collect()
+    }
+
+    /// The `parent` method returns the line number of the parent of `line`, if any.
+    pub fn parent(&self, line: u32) -> Option<u32> {
+        self.sums
+            .iter()
+            .find(|v| v.children.iter().any(|c| c.0 == line))
+            .map(|v| v.parent)
+    }
+
+    /// The `children` method returns the line numbers of the children of `line` in every
+    /// breakdown, sorted by line number.
+    pub fn children(&self, line: u32) -> Vec<u32> {
+        let mut children = self
+            .sums
+            .iter()
+            .filter(|v| v.parent == line)
+            .flat_map(|v| v.children.iter().map(|c| c.0))
+            .collect::<Vec<u32>>();
+        children.sort();
+        children
+    }
+
+    /// The `descendants` method returns the line numbers of the children of `line`, their
+    /// children and so on, sorted by line number.
+    pub fn descendants(&self, line: u32) -> Vec<u32> {
+        let mut descendants = Vec::new();
+        let mut stack = self.children(line);
+        while let Some(child) = stack.pop() {
+            descendants.push(child);
+            stack.extend(self.children(child));
+        }
+        descendants.sort();
+        descendants
+    }
+
+    /// The `depth` method returns the number of ancestors of `line`.
+    pub fn depth(&self, line: u32) -> usize {
+        let mut depth = 0;
+        let mut current = line;
+        while let Some(parent) = self.parent(current) {
+            depth += 1;
+            current = parent;
+        }
+        depth
+    }
+
+    /// The `tree` method returns the lines of the table with their parents and depths, sorted by
+    /// line number.
+    pub fn tree(&self) -> BeaLines {
+        BeaLines(
+            self.lines
+                .iter()
+                .map(|(line, description)| BeaLine {
+                    code: self.code(*line),
+                    line: *line,
+                    parent: self.parent(*line),
+                    depth: self.depth(*line),
+                    description: description.clone(),
+                })
+                .collect(),
+        )
+    }
+
+    /// The `rollup` method returns the value of `line` for `fips` in `year`.  When the value is
+    /// missing, it is summed from the first breakdown with all children available, rolling up
+    /// the children in turn.  Returns `None` if no breakdown can be summed.
+    pub fn rollup(&self, data: &BeaData, fips: i32, year: i32, line: u32) -> Option<f64> {
+        let records = self.records(data);
+        self.rollup_from(&records, fips, year, line)
+    }
+
+    fn rollup_from(
+        &self,
+        records: &HashMap<(i32, i32, u32), &BeaDatum>,
+        fips: i32,
+        year: i32,
+        line: u32,
+    ) -> Option<f64> {
+        if let Some(value) = records.get(&(fips, year, line)).and_then(|v| v.value()) {
+            return Some(value);
+        }
+        self.sums
+            .iter()
+            .filter(|v| v.parent == line)
+            .find_map(|sum| {
+                sum.children.iter().try_fold(0., |total, (child, add)| {
+                    let value = self.rollup_from(records, fips, year, *child)?;
+                    Some(if *add { total + value } else { total - value })
+                })
+            })
+    }
+
+    /// The `check` method compares each parent value in `data` to the sum of its children, for
+    /// every location and year.  Differences within rounding, half a unit of the published
+    /// precision for each value in the sum, are consistent.
+    pub fn check(&self, data: &BeaData) -> BeaChecks {
+        let records = self.records(data);
+        let mut keys = records
+            .keys()
+            .map(|(fips, year, _)| (*fips, *year))
+            .collect::<Vec<(i32, i32)>>();
+        keys.sort();
+        keys.dedup();
+        let mut checks = Vec::new();
+        for (fips, year) in keys {
+            for sum in &self.sums {
+                let parent = match records.get(&(fips, year, sum.parent)) {
+                    Some(parent) => parent,
+                    None => continue,
+                };
+                let mut total = 0.;
+                let mut missing = Vec::new();
+                for (child, add) in &sum.children {
+                    match records.get(&(fips, year, *child)).and_then(|v| v.value()) {
+                        Some(value) if *add => total += value,
+                        Some(value) => total -= value,
+                        None => missing.push(self.code(*child)),
+                    }
+                }
+                let tolerance =
+                    0.5 * (sum.children.len() + 1) as f64 * 10f64.powi(parent.unit_mult);
+                let difference = parent.value().map(|v| v - total);
+                let status = match difference {
+                    None => BeaCheckStatus::MissingParent,
+                    Some(_) if !missing.is_empty() => BeaCheckStatus::Suppressed,
+                    Some(difference) if difference.abs() <= tolerance => BeaCheckStatus::Consistent,
+                    Some(_) => BeaCheckStatus::Mismatch,
+                };
+                checks.push(BeaCheck {
+                    geo_fips: fips,
+                    time_period: year,
+                    code: self.code(sum.parent),
+                    children: sum.children.len(),
+                    missing: missing.join(" "),
+                    value: parent.value(),
+                    children_sum: total,
+                    difference,
+                    status,
+                });
+            }
+        }
+        BeaChecks(checks)
+    }
+
+    /// Indexes the records of the table in `data` by FIPS number, year and line number.
+    fn records<'a>(&self, data: &'a BeaData) -> HashMap<(i32, i32, u32), &'a BeaDatum> {
+        data.iter()
+            .filter_map(|v| match BeaTable::parse_code(&v.code) {
+                Ok((table, line)) if table == self.table => {
+                    Some(((v.geo_fips, v.time_period, line), v))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// The `BeaLines` struct holds a vector of type [`BeaLine`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Deref, DerefMut)]
+pub struct BeaLines(Vec<BeaLine>);
+
+impl BeaLines {
+    /// Writes the lines to a CSV file at location `path`.
+    pub fn to_csv<P: AsRef<std::path::Path>>(&mut self, path: P) -> Clean<()> {
+        to_csv(self, path)?;
+        Ok(())
+    }
+}
+
+/// The `BeaCheckStatus` enum is the outcome of comparing a parent value to the sum of its
+/// children.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum BeaCheckStatus {
+    /// The children sum to the parent within rounding.
+    Consistent,
+    /// One or more children are missing, so the difference is the total of the missing values.
+    Suppressed,
+    /// The children are all present but do not sum to the parent.
+    Mismatch,
+    /// The value of the parent is missing.
+    MissingParent,
+}
+
+/// The `BeaCheck` struct holds the comparison of a parent value to the sum of its children in one
+/// location and year.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BeaCheck {
+    /// The FIPS number of the location.
+    pub geo_fips: i32,
+    /// The year of the values.
+    pub time_period: i32,
+    /// The line code of the parent.
+    pub code: String,
+    /// The number of children in the sum.
+    pub children: usize,
+    /// The line codes of children with missing values, separated by spaces.
+    pub missing: String,
+    /// The value of the parent, if not missing.
+    pub value: Option<f64>,
+    /// The sum of the children with values.
+    pub children_sum: f64,
+    /// The parent value less the sum of the children.
+    pub difference: Option<f64>,
+    /// The outcome of the comparison.
+    pub status: BeaCheckStatus,
+}
+
+/// The `BeaChecks` struct holds a vector of type [`BeaCheck`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Deref, DerefMut)]
+pub struct BeaChecks(Vec<BeaCheck>);
+
+impl BeaChecks {
+    /// The `counts` method returns the number of checks with each status.
+    pub fn counts(&self) -> BTreeMap<BeaCheckStatus, usize> {
+        let mut counts = BTreeMap::new();
+        self.iter()
+            .for_each(|v| *counts.entry(v.status).or_insert(0) += 1);
+        counts
+    }
+
+    /// Writes the checks to a CSV file at location `path`.
+    pub fn to_csv<P: AsRef<std::path::Path>>(&mut self, path: P) -> Clean<()> {
+        to_csv(self, path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,159 @@
+use crate::import::bea::Deflator;
+use aid::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The `BeaMeasure` enum is the typed unit of measure of a BEA value, read from the `CL_UNIT`
+/// field and the line description.  Values in a measure are in whole units, after applying the
+/// `UNIT_MULT` multiplier, so "Thousands of dollars" and "Dollars" share the measure
+/// [`BeaMeasure::Dollars`], while "Thousands of chained 2017 dollars" is in
+/// [`BeaMeasure::RealDollars`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum BeaMeasure {
+    /// A dollar amount, such as personal income or earnings.
+    #[default]
+    Dollars,
+    /// A dollar amount per resident, such as per capita personal income.
+    DollarsPerCapita,
+    /// A dollar amount in constant, chained or real dollars, holding the unit label without the
+    /// multiplier, e.g. "Chained 2017 dollars".
+    RealDollars(String),
+    /// A count of persons, such as population.
+    Persons,
+    /// A count of jobs, such as full-time and part-time employment.
+    Jobs,
+    /// A pure number, such as a share or the ratio of two values in the same unit.
+    Ratio,
+    /// Any other unit, holding the unit description from BEA.
+    Other(String),
+}
+
+impl BeaMeasure {
+    /// Creates a new `BeaMeasure` from the unit description `unit` and the line `description`.
+    /// BEA reports per capita values in "Dollars", so the description distinguishes them from
+    /// other dollar amounts.  Rates such as "Thousands of dollars per household" cannot be summed
+    /// and are kept as [`BeaMeasure::Other`], as are the labels of derived measures, such as
+    /// "Dollars per job".
+    pub fn new(unit: &str, description: &str) -> Self {
+        let unit_lower = unit.trim().to_lowercase();
+        if unit_lower == "dollars per capita" {
+            Self::DollarsPerCapita
+        } else if unit_lower == "ratio" {
+            Self::Ratio
+        } else if is_derived(unit.trim()) || unit_lower.contains(" per ") {
+            Self::Other(unit.trim().to_string())
+        } else if unit_lower.contains("dollars") {
+            if !Deflator::is_nominal(&unit_lower) {
+                Self::RealDollars(real_label(&unit_lower))
+            } else if description.to_lowercase().contains("per capita") {
+                Self::DollarsPerCapita
+            } else {
+                Self::Dollars
+            }
+        } else if unit_lower.contains("persons") {
+            Self::Persons
+        } else if unit_lower.contains("jobs") {
+            Self::Jobs
+        } else {
+            Self::Other(unit.trim().to_string())
+        }
+    }
+
+    /// The `unit_mult` method returns the power of ten used to store derived values in the
+    /// measure as whole numbers.  Dollars and counts are stored in whole units, other dollar
+    /// amounts such as dollars per job in cents, and ratios and other units in millionths.
+    pub fn unit_mult(&self) -> i32 {
+        match self {
+            Self::Dollars
+            | Self::DollarsPerCapita
+            | Self::RealDollars(_)
+            | Self::Persons
+            | Self::Jobs => 0,
+            Self::Other(unit) if unit.to_lowercase().contains("dollar") => -2,
+            Self::Ratio | Self::Other(_) => -6,
+        }
+    }
+
+    /// The `check` method returns an error unless `other` is the same measure, for arithmetic
+    /// that cannot mix units.
+    pub fn check(&self, other: &Self) -> Clean<()> {
+        if self == other {
+            Ok(())
+        } else {
+            Err(Bandage::Hint(format!(
+                "Cannot combine {} with {}.",
+                self, other
+            )))
+        }
+    }
+
+    /// The `multiply` method returns the measure of the product of values in `self` and `other`.
+    pub fn multiply(&self, other: &Self) -> Self {
+        match (self, other) {
+            (Self::Ratio, measure) | (measure, Self::Ratio) => measure.clone(),
+            _ => Self::Other(format!("{} times {}", self, other.singular())),
+        }
+    }
+
+    /// The `divide` method returns the measure of the quotient of values in `self` by values in
+    /// `other`, e.g. "Dollars" divided by "Jobs" is "Dollars per job".
+    pub fn divide(&self, other: &Self) -> Self {
+        match (self, other) {
+            (measure, Self::Ratio) => measure.clone(),
+            (a, b) if a == b => Self::Ratio,
+            (Self::Dollars, Self::Persons) => Self::DollarsPerCapita,
+            _ => Self::Other(format!("{} per {}", self, other.singular())),
+        }
+    }
+
+    /// Returns the name of one unit of the measure, used in the labels of derived measures.
+    fn singular(&self) -> String {
+        match self {
+            Self::Dollars => "dollar".to_string(),
+            Self::DollarsPerCapita => "dollar per capita".to_string(),
+            Self::Persons => "person".to_string(),
+            Self::Jobs => "job".to_string(),
+            Self::Ratio => "ratio".to_string(),
+            Self::RealDollars(unit) | Self::Other(unit) => {
+                unit.to_lowercase().trim_end_matches('s').to_string()
+            }
+        }
+    }
+}
+
+/// Returns true if `unit` is the label of a derived measure, built by [`BeaMeasure::multiply`]
+/// or [`BeaMeasure::divide`] from the label of another measure, e.g. "Dollars per job".
+fn is_derived(unit: &str) -> bool {
+    ["Dollars", "Persons", "Jobs", "Ratio"].iter().any(|label| {
+        unit.strip_prefix(label)
+            .map(|rem| rem.starts_with(" per ") || rem.starts_with(" times "))
+            .unwrap_or(false)
+    })
+}
+
+/// Returns the label of a real dollar unit without the multiplier, e.g. "Chained 2017 dollars"
+/// for "millions of chained 2017 dollars".
+fn real_label(unit: &str) -> String {
+    let label = match unit.split_once(" of ") {
+        Some((scale, rest)) if matches!(scale, "thousands" | "millions" | "billions") => rest,
+        _ => unit,
+    };
+    let mut chars = label.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+impl fmt::Display for BeaMeasure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Dollars => write!(f, "Dollars"),
+            Self::DollarsPerCapita => write!(f, "Dollars per capita"),
+            Self::Persons => write!(f, "Persons"),
+            Self::Jobs => write!(f, "Jobs"),
+            Self::Ratio => write!(f, "Ratio"),
+            Self::RealDollars(unit) | Self::Other(unit) => write!(f, "{}", unit),
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use crate::import::bea::bea_expression::scale;
+use crate::import::bea::{BeaData, BeaDatum, BeaDerived, BeaMeasure, BeaValue};
+use aid::prelude::*;
+use derive_more::{Deref, DerefMut, From};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use tracing::{info, warn};
+
+/// The first FIPS number assigned to regions read without one.  BEA does not use FIPS numbers in
+/// the 99000 range.
+pub const REGION_FIPS: i32 = 99000;
+
+/// The per capita lines BEA computes from other lines of the same table, recomputed for regions
+/// from the aggregated components.
+const BEA_RATES: &[(&str, &str)] = &[
+    ("CAINC1-3", "CAINC1-1 / CAINC1-2"),
+    ("CAINC4-30", "CAINC4-10 / CAINC4-20"),
+    ("CAINC5N-30", "CAINC5N-10 / CAINC5N-20"),
+];
+
+/// The `RegionMissing` enum sets how [`BeaRegions::aggregate`] treats a region member with a
+/// missing value, such as a county with suppressed earnings in an industry.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RegionMissing {
+    /// The region value is missing for the same reason as the member, e.g. "(D)".
+    #[default]
+    Suppress,
+    /// The region value is the sum of the members with values, flagged as an estimate.
+    Partial,
+}
+
+impl std::str::FromStr for RegionMissing {
+    type Err = Bandage;
+
+    /// Reads the policy from its name, e.g. "suppress" or "partial".
+    fn from_str(input: &str) -> Clean<Self> {
+        match input.to_lowercase().as_str() {
+            "suppress" | "suppressed" => Ok(Self::Suppress),
+            "partial" => Ok(Self::Partial),
+            _ => Err(Bandage::Hint(format!(
+                "Invalid missing value policy: {}",
+                input
+            ))),
+        }
+    }
+}
+
+/// The `BeaRegion` struct defines a custom region as a group of member counties.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BeaRegion {
+    /// The name of the region, used as the location name of its records.
+    pub name: String,
+    /// The synthetic FIPS number of the region records.  Assigned from [`REGION_FIPS`] when
+    /// missing from the definition file.
+    #[serde(default)]
+    pub fips: i32,
+    /// The FIPS numbers of the member counties.
+    pub members: Vec<i32>,
+}
+
+impl BeaRegion {
+    /// Creates a new `BeaRegion` called `name` with FIPS number `fips` and `members`.
+    pub fn new(name: &str, fips: i32, members: &[i32]) -> Self {
+        Self {
+            name: name.to_string(),
+            fips,
+            members: members.to_vec(),
+        }
+    }
+
+    /// The `check` method returns an error if a member is listed more than once, which would
+    /// count its values twice.
+    pub fn check(&self) -> Clean<()> {
+        let mut seen = HashSet::new();
+        match self.members.iter().find(|v| !seen.insert(**v)) {
+            Some(fips) => Err(Bandage::Hint(format!(
+                "Duplicate member {} in region {}.",
+                fips, self.name
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Southern Oregon: Josephine, Jackson, Douglas, Curry and Klamath counties.
+    pub fn southern_oregon() -> Self {
+        Self::new(
+            "Southern Oregon",
+            REGION_FIPS + 1,
+            &[41033, 41029, 41019, 41015, 41035],
+        )
+    }
+}
+
+/// The `BeaRegions` struct holds a vector of type [`BeaRegion`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Deref, DerefMut, From)]
+pub struct BeaRegions(Vec<BeaRegion>);
+
+impl BeaRegions {
+    /// Reads the region definitions from a JSON array at `path`, e.g.
+    /// `[{"name": "Southern Oregon", "members": [41033, 41029, 41019, 41015, 41035]}]`.
+    /// Regions without a FIPS number are numbered in order from [`REGION_FIPS`].  Returns an
+    /// error if two regions share a FIPS number, or a region lists a member twice.
+    pub fn from_json<P: AsRef<std::path::Path>>(path: P) -> Clean<Self> {
+        let file = std::fs::File::open(path)?;
+        let mut regions: Vec<BeaRegion> =
+            serde_json::from_reader(file).map_err(|e| Bandage::Hint(e.to_string()))?;
+        let mut seen = HashSet::new();
+        for (i, region) in regions.iter_mut().enumerate() {
+            if region.fips == 0 {
+                region.fips = REGION_FIPS + i as i32 + 1;
+            }
+            region.check()?;
+            if !seen.insert(region.fips) {
+                return Err(Bandage::Hint(format!(
+                    "Duplicate region FIPS number {} for {}.",
+                    region.fips, region.name
+                )));
+            }
+        }
+        Ok(Self(regions))
+    }
+
+    /// The `aggregate` method returns records for each region, line code and year in `data`.
+    /// Dollars, persons and jobs are summed over the members.  BEA per capita lines are
+    /// recomputed from the summed components, and other lines that cannot be summed, such as
+    /// average earnings per job, are reported as not available.  Members with missing values
+    /// are treated according to `missing`, but a region without any member value is missing
+    /// for the reason of the members.  A sum including an estimate is an estimate.  Returns an
+    /// error if a region lists a member twice.
+    pub fn aggregate(&self, data: &BeaData, missing: RegionMissing) -> Clean<BeaData> {
+        for region in self.iter() {
+            region.check()?;
+        }
+        let mut groups: BTreeMap<(i32, String, i32), Vec<&BeaDatum>> = BTreeMap::new();
+        for region in self.iter() {
+            for record in data.iter().filter(|v| region.members.contains(&v.geo_fips)) {
+                groups
+                    .entry((region.fips, record.code.clone(), record.time_period))
+                    .or_default()
+                    .push(record);
+            }
+        }
+        let names = self
+            .iter()
+            .map(|v| (v.fips, v.name.as_str()))
+            .collect::<HashMap<i32, &str>>();
+        let sizes = self
+            .iter()
+            .map(|v| (v.fips, v.members.len()))
+            .collect::<HashMap<i32, usize>>();
+        let mut records = Vec::new();
+        let mut skipped: BTreeMap<String, Vec<(i32, i32, &BeaDatum)>> = BTreeMap::new();
+        let mut partial = 0;
+        for ((fips, code, year), members) in groups {
+            let first = members[0];
+            if !matches!(
+                first.measure(),
+                BeaMeasure::Dollars | BeaMeasure::Persons | BeaMeasure::Jobs
+            ) {
+                skipped.entry(code).or_default().push((fips, year, first));
+                continue;
+            }
+            let mut total = 0;
+            let mut sum = 0.;
+            let mut reason = None;
+            let mut counted = false;
+            let estimate = members.iter().any(|v| v.data_value.is_estimate());
+            for member in &members {
+                match (member.data_value.number(), member.value()) {
+                    (Some(number), Some(value)) => {
+                        total += number;
+                        sum += value;
+                        counted = true;
+                    }
+                    _ => {
+                        reason.get_or_insert_with(|| member.data_value.clone());
+                    }
+                }
+            }
+            if members.len() < sizes[&fips] {
+                reason.get_or_insert(BeaValue::NotAvailable);
+            }
+            // Members of a line code share the unit multiplier, so the published values sum
+            // exactly.  Otherwise the sum in whole units is scaled.
+            let (number, unit_mult) = if members.iter().all(|v| v.unit_mult == first.unit_mult) {
+                (total, first.unit_mult)
+            } else {
+                match scale(sum) {
+                    (BeaValue::Number(number), unit_mult) => (number, unit_mult),
+                    _ => (0, 0),
+                }
+            };
+            let (data_value, unit_mult) = match (reason, missing) {
+                (None, _) if estimate => (BeaValue::Estimate(number), unit_mult),
+                (None, _) => (BeaValue::Number(number), unit_mult),
+                (Some(reason), RegionMissing::Suppress) => (reason, first.unit_mult),
+                (Some(reason), RegionMissing::Partial) if !counted => (reason, first.unit_mult),
+                (Some(_), RegionMissing::Partial) => {
+                    partial += 1;
+                    (BeaValue::Estimate(number), unit_mult)
+                }
+            };
+            records.push(BeaDatum {
+                code,
+                geo_fips: fips,
+                geo_name: names[&fips].to_string(),
+                time_period: year,
+                description: first.description.clone(),
+                cl_unit: first.cl_unit.clone(),
+                unit_mult,
+                data_value,
+            });
+        }
+        info!("{} partial region sums.", partial);
+        let mut data = BeaData::from(records);
+        for (code, expression) in BEA_RATES {
+            if let Some(groups) = skipped.remove(*code) {
+                let description = &groups[0].2.description;
+                let rates = BeaDerived::new(code, description, expression)?.evaluate(&data)?;
+                data.extend(rates);
+            }
+        }
+        if !skipped.is_empty() {
+            warn!(
+                "Cannot sum over a region, reporting as not available: {}",
+                skipped.keys().cloned().collect::<Vec<_>>().join(", ")
+            );
+        }
+        for (code, groups) in skipped {
+            for (fips, year, first) in groups {
+                data.push(BeaDatum {
+                    code: code.clone(),
+                    geo_fips: fips,
+                    geo_name: names[&fips].to_string(),
+                    time_period: year,
+                    data_value: BeaValue::NotAvailable,
+                    ..first.clone()
+                });
+            }
+        }
+        Ok(data)
+    }
+}
@@ -0,0 +1,392 @@
+use crate::import::bea::{BeaData, BeaMeasure, BeaValue};
+use aid::prelude::*;
+use derive_more::{Deref, DerefMut};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The `BeaSeries` struct holds the values of one line code in one location, keyed by year.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BeaSeries {
+    /// The FIPS number of the location.
+    pub geo_fips: i32,
+    /// The FIPS description of the location.
+    pub geo_name: String,
+    /// The BEA line code.
+    pub code: String,
+    /// The description of the line code.
+    pub description: String,
+    /// The typed unit of measure of the values.
+    pub measure: BeaMeasure,
+    /// The label of the unit of measure, which also records any conversion to constant dollars.
+    pub unit: String,
+    /// The unit of measure reported by BEA, e.g. "Millions of chained 2017 dollars", which
+    /// records whether the values are in current dollars.
+    pub cl_unit: String,
+    /// The values of the series, keyed by year.
+    pub values: BTreeMap<i32, f64>,
+    /// The BEA codes explaining missing values, such as "(D)" for suppressed, keyed by year.
+    pub notes: BTreeMap<i32, BeaValue>,
+}
+
+impl BeaSeries {
+    /// The `value` method returns the value of the series in `year`.
+    pub fn value(&self, year: i32) -> Option<f64> {
+        self.values.get(&year).copied()
+    }
+
+    /// The `note` method returns the BEA code explaining why the value in `year` is missing, if
+    /// BEA reported one.
+    pub fn note(&self, year: i32) -> Option<&BeaValue> {
+        self.notes.get(&year)
+    }
+
+    /// The `is_nominal` method returns `true` if the series is in current dollars, as described
+    /// by [`Deflator::is_nominal`] for the unit reported by BEA.
+    pub fn is_nominal(&self) -> bool {
+        Deflator::is_nominal(&self.cl_unit)
+    }
+
+    /// The `change` method returns the change in value from the year before `year`.
+    pub fn change(&self, year: i32) -> Option<f64> {
+        Some(self.value(year)? - self.value(year - 1)?)
+    }
+
+    /// The `percent_change` method returns the percent change in value from the year before
+    /// `year`.  Returns `None` if the previous value is zero.
+    pub fn percent_change(&self, year: i32) -> Option<f64> {
+        let previous = self.value(year - 1)?;
+        if previous == 0. {
+            return None;
+        }
+        Some(100. * (self.value(year)? - previous) / previous)
+    }
+
+    /// The `cagr` method returns the compound annual growth rate from `start` to `end`, as a
+    /// percentage.  Returns `None` if either value is missing, the values are not positive, or
+    /// `end` is not after `start`.
+    pub fn cagr(&self, start: i32, end: i32) -> Option<f64> {
+        let first = self.value(start)?;
+        let last = self.value(end)?;
+        if end <= start || first <= 0. || last <= 0. {
+            return None;
+        }
+        Some(100. * ((last / first).powf(1. / (end - start) as f64) - 1.))
+    }
+
+    /// The `check` method returns an error unless `other` has the same measure and unit label,
+    /// so that arithmetic cannot mix units, or current and constant dollars.
+    pub fn check(&self, other: &Self) -> Clean<()> {
+        self.measure.check(&other.measure)?;
+        if self.unit != other.unit {
+            return Err(Bandage::Hint(format!(
+                "Cannot combine {} with {}.",
+                self.unit, other.unit
+            )));
+        }
+        Ok(())
+    }
+
+    /// The `combine` method returns a series with the values produced by `f` from the values of
+    /// `self` and `other` in each year both are present.
+    fn combine<F: Fn(f64, f64) -> Option<f64>>(&self, other: &Self, f: F) -> Self {
+        let values = self
+            .values
+            .iter()
+            .filter_map(|(year, a)| {
+                other
+                    .values
+                    .get(year)
+                    .and_then(|b| f(*a, *b))
+                    .map(|v| (*year, v))
+            })
+            .collect();
+        Self {
+            values,
+            ..self.clone()
+        }
+    }
+
+    /// The `add` method returns the sum of `self` and `other` in each year both are present.
+    /// Returns an error if the series are in different units.
+    pub fn add(&self, other: &Self) -> Clean<Self> {
+        self.check(other)?;
+        Ok(self.combine(other, |a, b| Some(a + b)))
+    }
+
+    /// The `subtract` method returns `other` subtracted from `self` in each year both are
+    /// present.  Returns an error if the series are in different units.
+    pub fn subtract(&self, other: &Self) -> Clean<Self> {
+        self.check(other)?;
+        Ok(self.combine(other, |a, b| Some(a - b)))
+    }
+
+    /// The `per_capita` method divides a dollar series by a `population` series in persons,
+    /// returning a series in dollars per capita.  Returns an error for any other units.
+    pub fn per_capita(&self, population: &Self) -> Clean<Self> {
+        if self.measure != BeaMeasure::Dollars || population.measure != BeaMeasure::Persons {
+            return Err(Bandage::Hint(format!(
+                "Cannot divide {} by {}.",
+                self.unit, population.unit
+            )));
+        }
+        let mut series = self.combine(population, |a, b| if b > 0. { Some(a / b) } else { None });
+        series.measure = BeaMeasure::DollarsPerCapita;
+        series.unit = self.unit.replacen("Dollars", "Dollars per capita", 1);
+        Ok(series)
+    }
+
+    /// The `map` method returns a copy of the series with the values produced by `f`, which
+    /// receives the series and each year, dropping years where `f` returns `None`.
+    fn map<F: Fn(&Self, i32) -> Option<f64>>(&self, f: F) -> Self {
+        let values = self
+            .values
+            .keys()
+            .filter_map(|year| f(self, *year).map(|v| (*year, v)))
+            .collect();
+        Self {
+            values,
+            ..self.clone()
+        }
+    }
+
+    /// The `changes` method returns the series of year-over-year changes.
+    pub fn changes(&self) -> Self {
+        self.map(|s, year| s.change(year))
+    }
+
+    /// The `percent_changes` method returns the series of year-over-year percent changes.
+    pub fn percent_changes(&self) -> Self {
+        self.map(|s, year| s.percent_change(year))
+    }
+
+    /// The `index` method returns the series indexed to 100 in year `base`.  Returns an empty
+    /// series if the base year is missing or zero.
+    pub fn index(&self, base: i32) -> Self {
+        match self.value(base) {
+            Some(value) if value != 0. => {
+                self.map(|s, year| s.value(year).map(|v| 100. * v / value))
+            }
+            _ => self.map(|_, _| None),
+        }
+    }
+}
+
+/// The `BeaPivot` struct holds a vector of type [`BeaSeries`], one for each pair of location and
+/// line code, sorted by FIPS number then line code.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Deref, DerefMut)]
+pub struct BeaPivot(Vec<BeaSeries>);
+
+impl BeaPivot {
+    /// The `years` method returns the years present in any series, in ascending order.
+    pub fn years(&self) -> Vec<i32> {
+        self.iter()
+            .flat_map(|v| v.values.keys().chain(v.notes.keys()).copied())
+            .collect::<BTreeSet<i32>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// The `changes` method returns the year-over-year change of each series.
+    pub fn changes(&self) -> Self {
+        Self(self.iter().map(|v| v.changes()).collect())
+    }
+
+    /// The `percent_changes` method returns the year-over-year percent change of each series.
+    pub fn percent_changes(&self) -> Self {
+        Self(self.iter().map(|v| v.percent_changes()).collect())
+    }
+
+    /// The `index` method returns each series indexed to 100 in year `base`.
+    pub fn index(&self, base: i32) -> Self {
+        Self(self.iter().map(|v| v.index(base)).collect())
+    }
+
+    /// The `deflate` method converts each dollar series to constant dollars of year `base` using
+    /// `deflator`.  Series not measured in current dollars are returned unchanged.
+    pub fn deflate(&self, deflator: &Deflator, base: i32) -> Clean<Self> {
+        let mut records = Vec::new();
+        for series in self.iter() {
+            if series.is_nominal() {
+                records.push(deflator.deflate(series, base)?);
+            } else {
+                tracing::trace!("Not deflating {} in {}.", series.code, series.unit);
+                records.push(series.clone());
+            }
+        }
+        Ok(Self(records))
+    }
+
+    /// Write the contents of `BeaPivot` to a CSV file at location `path`, with one row for each
+    /// series and one column for each year.  Missing values hold the BEA code explaining them,
+    /// such as "(D)".  When `cagr` holds a start and end year, a final column holds the compound
+    /// annual growth rate between them.
+    pub fn to_csv<P: AsRef<std::path::Path>>(
+        &self,
+        path: P,
+        cagr: Option<(i32, i32)>,
+    ) -> Clean<()> {
+        let years = self.years();
+        let mut wtr = csv::Writer::from_writer(std::fs::File::create(path)?);
+        let mut header = vec![
+            "fips".to_string(),
+            "name".to_string(),
+            "code".to_string(),
+            "description".to_string(),
+            "unit".to_string(),
+        ];
+        header.extend(years.iter().map(|v| v.to_string()));
+        if let Some((start, end)) = cagr {
+            header.push(format!("cagr {}-{}", start, end));
+        }
+        wtr.write_record(&header)
+            .map_err(|e| Bandage::Hint(e.to_string()))?;
+        for series in self.iter() {
+            let mut record = vec![
+                series.geo_fips.to_string(),
+                series.geo_name.clone(),
+                series.code.clone(),
+                series.description.clone(),
+                series.unit.clone(),
+            ];
+            record.extend(years.iter().map(|year| {
+                match series.value(*year) {
+                    Some(value) => value.to_string(),
+                    None => series
+                        .note(*year)
+                        .map(|v| v.code().to_string())
+                        .unwrap_or_default(),
+                }
+            }));
+            if let Some((start, end)) = cagr {
+                record.push(match series.cagr(start, end) {
+                    Some(value) => value.to_string(),
+                    None => String::new(),
+                });
+            }
+            wtr.write_record(&record)
+                .map_err(|e| Bandage::Hint(e.to_string()))?;
+        }
+        wtr.flush()?;
+        Ok(())
+    }
+}
+
+impl From<&BeaData> for BeaPivot {
+    /// Pivots the records in `data` into one series for each pair of FIPS number and line code.
+    fn from(data: &BeaData) -> Self {
+        let mut series: BTreeMap<(i32, String), BeaSeries> = BTreeMap::new();
+        for record in data.iter() {
+            let entry = series
+                .entry((record.geo_fips, record.code.clone()))
+                .or_insert_with(|| BeaSeries {
+                    geo_fips: record.geo_fips,
+                    geo_name: record.geo_name.clone(),
+                    code: record.code.clone(),
+                    description: record.description.clone(),
+                    measure: record.measure(),
+                    unit: record.measure().to_string(),
+                    cl_unit: record.cl_unit.clone(),
+                    values: BTreeMap::new(),
+                    notes: BTreeMap::new(),
+                });
+            match record.value() {
+                Some(value) => {
+                    entry.values.insert(record.time_period, value);
+                }
+                None => {
+                    entry
+                        .notes
+                        .insert(record.time_period, record.data_value.clone());
+                }
+            }
+        }
+        Self(series.into_values().collect())
+    }
+}
+
+/// The `DeflatorRow` struct holds one year of a price index read from a CSV file with the columns
+/// "year" and "value".
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DeflatorRow {
+    /// The year of the index value.
+    pub year: i32,
+    /// The value of the price index.
+    pub value: f64,
+}
+
+/// The `Deflator` struct holds an annual price index, such as the PCE price index or CPI-U, used
+/// to convert current dollars to constant dollars.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Deflator {
+    /// The name of the index, e.g. "PCE" or "CPI-U", used in the unit label of deflated series.
+    pub name: String,
+    /// The value of the index, keyed by year.
+    pub values: BTreeMap<i32, f64>,
+}
+
+impl Deflator {
+    /// Reads a `Deflator` named `name` from a CSV file at location `path`, with the columns
+    /// "year" and "value".
+    pub fn from_csv<P: AsRef<std::path::Path>>(path: P, name: &str) -> Clean<Self> {
+        let records: Vec<DeflatorRow> = crate::utils::from_csv(path)?;
+        Ok(Self {
+            name: name.to_string(),
+            values: records.iter().map(|v| (v.year, v.value)).collect(),
+        })
+    }
+
+    /// The `is_nominal` method returns `true` if `unit` describes current dollars, i.e. it
+    /// mentions dollars and is not already in constant, chained or real dollars.
+    pub fn is_nominal(unit: &str) -> bool {
+        let unit = unit.to_lowercase();
+        let words = unit
+            .split(|c: char| !c.is_alphanumeric())
+            .collect::<Vec<&str>>();
+        words.contains(&"dollars")
+            && !words
+                .iter()
+                .any(|v| matches!(*v, "constant" | "chained" | "real"))
+    }
+
+    /// The `unit` method returns the unit label of a series in `unit` after conversion to
+    /// constant dollars of year `base`, e.g. "Dollars (constant 2017 dollars, PCE)".
+    pub fn unit(&self, unit: &str, base: i32) -> String {
+        format!("{} (constant {} dollars, {})", unit, base, self.name)
+    }
+
+    /// The `deflate` method converts `series` to constant dollars of year `base`, multiplying the
+    /// value in each year by the ratio of the index in `base` to the index in that year.  Returns
+    /// an error if the series is not in current dollars, including a series already deflated,
+    /// if the index is missing for the base
+    /// year, or if the index is missing for a year of the series.
+    pub fn deflate(&self, series: &BeaSeries, base: i32) -> Clean<BeaSeries> {
+        if !series.is_nominal() {
+            return Err(Bandage::Hint(format!(
+                "Cannot deflate {} measured in {}.",
+                series.code, series.cl_unit
+            )));
+        }
+        let base_value = self.index(base)?;
+        let mut values = BTreeMap::new();
+        for (year, value) in &series.values {
+            values.insert(*year, value * base_value / self.index(*year)?);
+        }
+        Ok(BeaSeries {
+            unit: self.unit(&series.unit, base),
+            cl_unit: self.unit(&series.cl_unit, base),
+            values,
+            ..series.clone()
+        })
+    }
+
+    /// Returns the positive index value for `year`, or an error if it is missing.
+    fn index(&self, year: i32) -> Clean<f64> {
+        match self.values.get(&year) {
+            Some(value) if *value > 0. => Ok(*value),
+            _ => Err(Bandage::Hint(format!(
+                "Deflator {} has no value for {}.",
+                self.name, year
+            ))),
+        }
+    }
+}
@@ -0,0 +1,380 @@
+use aid::prelude::*;
+use nom::bytes::complete::{tag, take_while1};
+use nom::character::complete::digit1;
+use nom::IResult;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use tracing::warn;
+
+/// The personal income summary lines of CAINC1.
+const CAINC1_LINES: &[(u32, &str)] = &[
+    (1, "Personal income"),
+    (2, "Population"),
+    (3, "Per capita personal income"),
+];
+
+/// The derivation of personal income, shared by CAINC4 and CAINC5N.
+const INCOME_LINES: &[(u32, &str)] = &[
+    (10, "Personal income"),
+    (20, "Population"),
+    (30, "Per capita personal income"),
+    (35, "Earnings by place of work"),
+    (36, "Less: Contributions for government social insurance"),
+    (
+        37,
+        "Employee and self-employed contributions for government social insurance",
+    ),
+    (38, "Employer contributions for government social insurance"),
+    (42, "Plus: Adjustment for residence"),
+    (45, "Equals: Net earnings by place of residence"),
+    (46, "Plus: Dividends, interest, and rent"),
+    (47, "Plus: Personal current transfer receipts"),
+    (50, "Wages and salaries"),
+    (60, "Supplements to wages and salaries"),
+    (
+        61,
+        "Employer contributions for employee pension and insurance funds",
+    ),
+    (62, "Employer contributions for government social insurance"),
+    (70, "Proprietors' income"),
+    (71, "Farm proprietors' income"),
+    (72, "Nonfarm proprietors' income"),
+];
+
+/// The farm and nonfarm split of personal income and the employment lines in CAINC4.
+const CAINC4_LINES: &[(u32, &str)] = &[
+    (11, "Nonfarm personal income"),
+    (12, "Farm income"),
+    (7010, "Total employment"),
+    (7020, "Wage and salary employment"),
+    (7040, "Proprietors employment"),
+    (7050, "Farm proprietors employment"),
+    (7060, "Nonfarm proprietors employment"),
+];
+
+/// The earnings aggregates above the industry lines in CAINC5N.
+const CAINC5N_LINES: &[(u32, &str)] = &[
+    (81, "Farm earnings"),
+    (82, "Nonfarm earnings"),
+    (90, "Private nonfarm earnings"),
+];
+
+/// The NAICS subsector lines below the sector lines in CAINC5N.  CAEMP25N publishes the sector
+/// lines only.
+const SUBSECTOR_LINES: &[(u32, &str)] = &[
+    (101, "Forestry and logging"),
+    (102, "Fishing, hunting and trapping"),
+    (103, "Agriculture and forestry support activities"),
+    (201, "Oil and gas extraction"),
+    (202, "Mining (except oil and gas)"),
+    (203, "Support activities for mining"),
+    (510, "Durable goods manufacturing"),
+    (511, "Wood product manufacturing"),
+    (512, "Nonmetallic mineral product manufacturing"),
+    (513, "Primary metal manufacturing"),
+    (514, "Fabricated metal product manufacturing"),
+    (515, "Machinery manufacturing"),
+    (516, "Computer and electronic product manufacturing"),
+    (
+        517,
+        "Electrical equipment, appliance, and component manufacturing",
+    ),
+    (
+        518,
+        "Motor vehicles, bodies and trailers, and parts manufacturing",
+    ),
+    (519, "Other transportation equipment manufacturing"),
+    (521, "Furniture and related product manufacturing"),
+    (522, "Miscellaneous manufacturing"),
+    (530, "Nondurable goods manufacturing"),
+    (531, "Food manufacturing"),
+    (532, "Beverage and tobacco product manufacturing"),
+    (533, "Textile mills"),
+    (534, "Textile product mills"),
+    (535, "Apparel manufacturing"),
+    (536, "Leather and allied product manufacturing"),
+    (537, "Paper manufacturing"),
+    (538, "Printing and related support activities"),
+    (539, "Petroleum and coal products manufacturing"),
+    (541, "Chemical manufacturing"),
+    (542, "Plastics and rubber products manufacturing"),
+    (701, "Motor vehicle and parts dealers"),
+    (702, "Furniture and home furnishings stores"),
+    (703, "Electronics and appliance stores"),
+    (
+        704,
+        "Building material and garden equipment and supplies dealers",
+    ),
+    (705, "Food and beverage stores"),
+    (706, "Health and personal care stores"),
+    (707, "Gasoline stations"),
+    (708, "Clothing and clothing accessories stores"),
+    (
+        709,
+        "Sporting goods, hobby, musical instrument, and book stores",
+    ),
+    (711, "General merchandise stores"),
+    (712, "Miscellaneous store retailers"),
+    (713, "Nonstore retailers"),
+    (801, "Air transportation"),
+    (802, "Rail transportation"),
+    (803, "Water transportation"),
+    (804, "Truck transportation"),
+    (805, "Transit and ground passenger transportation"),
+    (806, "Pipeline transportation"),
+    (807, "Scenic and sightseeing transportation"),
+    (808, "Support activities for transportation"),
+    (809, "Couriers and messengers"),
+    (811, "Warehousing and storage"),
+    (901, "Publishing industries (except Internet)"),
+    (902, "Motion picture and sound recording industries"),
+    (903, "Broadcasting (except Internet)"),
+    (904, "Telecommunications"),
+    (905, "Data processing, hosting, and related services"),
+    (906, "Other information services"),
+    (1001, "Monetary authorities-central bank"),
+    (1002, "Credit intermediation and related activities"),
+    (
+        1003,
+        "Securities, commodity contracts, and other financial investments and related activities",
+    ),
+    (1004, "Insurance carriers and related activities"),
+    (1005, "Funds, trusts, and other financial vehicles"),
+    (1101, "Real estate"),
+    (1102, "Rental and leasing services"),
+    (
+        1103,
+        "Lessors of nonfinancial intangible assets (except copyrighted works)",
+    ),
+    (1401, "Administrative and support services"),
+    (1402, "Waste management and remediation services"),
+    (1601, "Ambulatory health care services"),
+    (1602, "Hospitals"),
+    (1603, "Nursing and residential care facilities"),
+    (1604, "Social assistance"),
+    (
+        1701,
+        "Performing arts, spectator sports, museums, and related activities",
+    ),
+    (1702, "Amusement, gambling, and recreation industries"),
+    (1801, "Accommodation"),
+    (1802, "Food services and drinking places"),
+    (1901, "Repair and maintenance"),
+    (1902, "Personal and laundry services"),
+    (
+        1903,
+        "Religious, grantmaking, civic, professional, and similar organizations",
+    ),
+    (1904, "Private households"),
+];
+
+/// The employment lines above the industry lines in CAEMP25N.
+const CAEMP25N_LINES: &[(u32, &str)] = &[
+    (10, "Total employment"),
+    (20, "Wage and salary employment"),
+    (40, "Proprietors employment"),
+    (50, "Farm proprietors employment"),
+    (60, "Nonfarm proprietors employment"),
+    (70, "Farm employment"),
+    (80, "Nonfarm employment"),
+    (90, "Private nonfarm employment"),
+];
+
+/// The NAICS sector and government lines, shared by CAINC5N and CAEMP25N.
+const INDUSTRY_LINES: &[(u32, &str)] = &[
+    (100, "Forestry, fishing, and related activities"),
+    (200, "Mining, quarrying, and oil and gas extraction"),
+    (300, "Utilities"),
+    (400, "Construction"),
+    (500, "Manufacturing"),
+    (600, "Wholesale trade"),
+    (700, "Retail trade"),
+    (800, "Transportation and warehousing"),
+    (900, "Information"),
+    (1000, "Finance and insurance"),
+    (1100, "Real estate and rental and leasing"),
+    (1200, "Professional, scientific, and technical services"),
+    (1300, "Management of companies and enterprises"),
+    (
+        1400,
+        "Administrative and support and waste management and remediation services",
+    ),
+    (1500, "Educational services"),
+    (1600, "Health care and social assistance"),
+    (1700, "Arts, entertainment, and recreation"),
+    (1800, "Accommodation and food services"),
+    (
+        1900,
+        "Other services (except government and government enterprises)",
+    ),
+    (2000, "Government and government enterprises"),
+    (2001, "Federal civilian"),
+    (2002, "Military"),
+    (2010, "State and local"),
+    (2011, "State government"),
+    (2012, "Local government"),
+];
+
+/// The `BeaTable` enum identifies the BEA Regional table a record comes from.  Line codes in the
+/// BEA data take the form "CAINC5N-10", where the part before the dash names the table and the
+/// part after is the line number within the table.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum BeaTable {
+    /// CAINC1: Personal Income Summary: Personal Income, Population, Per Capita Personal Income.
+    Cainc1,
+    /// CAINC4: Personal Income and Employment by Major Component.
+    Cainc4,
+    /// CAINC5N: Personal Income by Major Component and Earnings by NAICS Industry.
+    Cainc5n,
+    /// CAINC30: Economic Profile.
+    Cainc30,
+    /// CAEMP25N: Total Full-Time and Part-Time Employment by NAICS Industry.
+    Caemp25n,
+    /// Any other table, holding the table name as it appears in the line code.
+    Other(String),
+}
+
+impl BeaTable {
+    /// The `name` method returns the table name as it appears in BEA line codes, e.g. "CAINC5N".
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Cainc1 => "CAINC1",
+            Self::Cainc4 => "CAINC4",
+            Self::Cainc5n => "CAINC5N",
+            Self::Cainc30 => "CAINC30",
+            Self::Caemp25n => "CAEMP25N",
+            Self::Other(name) => name,
+        }
+    }
+
+    /// The `description` method returns the title of the table.
+    pub fn description(&self) -> &str {
+        match self {
+            Self::Cainc1 => {
+                "Personal Income Summary: Personal Income, Population, Per Capita Personal Income"
+            }
+            Self::Cainc4 => "Personal Income and Employment by Major Component",
+            Self::Cainc5n => "Personal Income by Major Component and Earnings by NAICS Industry",
+            Self::Cainc30 => "Economic Profile",
+            Self::Caemp25n => "Total Full-Time and Part-Time Employment by NAICS Industry",
+            Self::Other(_) => "Other BEA Regional table",
+        }
+    }
+
+    /// The `line_codes` method returns the built-in catalog of published lines in the table, a
+    /// BTreeMap of line numbers and descriptions.  The catalog holds the summary lines of each
+    /// table, the NAICS sector lines of CAINC5N and CAEMP25N, and the subsector lines of CAINC5N.
+    /// The built-in catalog is empty for CAINC30 and other tables, which read their lines from a
+    /// BEA line-code file with [`BeaTable::catalog`].
+    pub fn line_codes(&self) -> BTreeMap<u32, String> {
+        let parts: &[&[(u32, &str)]] = match self {
+            Self::Cainc1 => &[CAINC1_LINES],
+            Self::Cainc4 => &[INCOME_LINES, CAINC4_LINES],
+            Self::Cainc5n => &[INCOME_LINES, CAINC5N_LINES, INDUSTRY_LINES, SUBSECTOR_LINES],
+            Self::Caemp25n => &[CAEMP25N_LINES, INDUSTRY_LINES],
+            Self::Cainc30 | Self::Other(_) => &[],
+        };
+        parts
+            .iter()
+            .flat_map(|v| v.iter())
+            .map(|(line, description)| (*line, description.to_string()))
+            .collect()
+    }
+
+    /// The `catalog` method returns the catalog of the table from [`BeaTable::line_codes`],
+    /// extended with the lines of the BEA line-code file at the path named by the environmental
+    /// variable [`BeaTable::lines_var`], if set.  The file is a CSV with the columns "LineCode"
+    /// and "Description", and its descriptions take precedence.  If the file cannot be read, the
+    /// built-in catalog is returned with a warning.
+    pub fn catalog(&self) -> BTreeMap<u32, String> {
+        let mut catalog = self.line_codes();
+        if let Ok(path) = std::env::var(self.lines_var()) {
+            match Self::read_lines(&path) {
+                Ok(lines) => catalog.extend(lines),
+                Err(e) => warn!("Cannot read the {} lines at {}: {}", self, path, e),
+            }
+        }
+        catalog
+    }
+
+    /// The `read_lines` method reads a BEA line-code file at `path`, a CSV with the columns
+    /// "LineCode" and "Description", returning a BTreeMap of line numbers and descriptions.
+    pub fn read_lines<P: AsRef<std::path::Path>>(path: P) -> Clean<BTreeMap<u32, String>> {
+        let mut rdr = csv::Reader::from_reader(std::fs::File::open(path)?);
+        let mut lines = BTreeMap::new();
+        for result in rdr.deserialize() {
+            let line: BeaLineCode = result.map_err(|e| Bandage::Hint(e.to_string()))?;
+            lines.insert(line.line_code, line.description.trim().to_string());
+        }
+        Ok(lines)
+    }
+
+    /// The `lines_var` method returns the name of the environmental variable holding the path to
+    /// the BEA line-code file of the table, e.g. "BEA_CAINC30_LINES".
+    pub fn lines_var(&self) -> String {
+        format!("BEA_{}_LINES", self.name())
+    }
+
+    /// The `csv_var` method returns the name of the environmental variable holding the path to
+    /// the processed CSV of the table, e.g. "BEA_CAINC5N_CSV".
+    pub fn csv_var(&self) -> String {
+        format!("BEA_{}_CSV", self.name())
+    }
+
+    /// The `raw_var` method returns the name of the environmental variable holding the path to
+    /// the raw download of the table, e.g. "BEA_CAINC5N_RAW".
+    pub fn raw_var(&self) -> String {
+        format!("BEA_{}_RAW", self.name())
+    }
+
+    /// The `parse_code` method reads the table and line number from a BEA line code such as
+    /// "CAINC5N-10".
+    pub fn parse_code(code: &str) -> Clean<(Self, u32)> {
+        match parse_code(code.trim()) {
+            Ok(("", (table, line))) => Ok((table.parse()?, line.parse::<u32>()?)),
+            _ => Err(Bandage::Hint(format!("Invalid BEA line code: {}", code))),
+        }
+    }
+}
+
+impl fmt::Display for BeaTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl std::str::FromStr for BeaTable {
+    type Err = Bandage;
+
+    /// Reads the table from its name, ignoring case, e.g. "CAINC5N" or "cainc1".
+    fn from_str(input: &str) -> Clean<Self> {
+        let name = input.trim().to_uppercase();
+        match name.as_str() {
+            "CAINC1" => Ok(Self::Cainc1),
+            "CAINC4" => Ok(Self::Cainc4),
+            "CAINC5N" => Ok(Self::Cainc5n),
+            "CAINC30" => Ok(Self::Cainc30),
+            "CAEMP25N" => Ok(Self::Caemp25n),
+            "" => Err(Bandage::Hint("Missing BEA table name.".to_string())),
+            _ => Ok(Self::Other(name)),
+        }
+    }
+}
+
+/// One row of a BEA line-code file, read by [`BeaTable::read_lines`].
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct BeaLineCode {
+    line_code: u32,
+    description: String,
+}
+
+/// Splits a BEA line code into the table name and line number.  Called by
+/// [`BeaTable::parse_code`].
+fn parse_code(input: &str) -> IResult<&str, (&str, &str)> {
+    let (rem, table) = take_while1(|c: char| c.is_ascii_alphanumeric())(input)?;
+    let (rem, _) = tag("-")(rem)?;
+    let (rem, line) = digit1(rem)?;
+    Ok((rem, (table, line)))
+}
@@ -0,0 +1,162 @@
+use aid::prelude::*;
+use nom::bytes::complete::tag;
+use nom::character::complete::digit1;
+use nom::combinator::opt;
+use nom::multi::many0;
+use nom::sequence::preceded;
+use nom::IResult;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The `BeaValue` enum holds the data value of a BEA record, which is either a number or a code
+/// explaining why the number is missing.  BEA marks estimates with a trailing "E", and reports
+/// missing values with the codes "(D)", "(NA)", "(NM)" and "(L)".  Values serialize to the same
+/// text form BEA uses, so a suppressed cell reads "(D)" rather than a blank or a zero.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub enum BeaValue {
+    /// A reported value.
+    Number(i64),
+    /// A value estimated by BEA, marked with a trailing "E".
+    Estimate(i64),
+    /// "(D)": Not shown to avoid disclosure of confidential information.
+    Suppressed,
+    /// "(NA)": Not available.
+    NotAvailable,
+    /// "(NM)": Not meaningful.
+    NotMeaningful,
+    /// "(L)": Less than $50,000, but the estimates for this item are included in the totals.
+    Less,
+    /// Any other code, holding the code as it appears in the data.
+    Other(String),
+}
+
+impl BeaValue {
+    /// The `number` method returns the numeric value, if any, including estimates.
+    pub fn number(&self) -> Option<i64> {
+        match self {
+            Self::Number(value) | Self::Estimate(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// The `is_estimate` method returns `true` if BEA flagged the value as an estimate.
+    pub fn is_estimate(&self) -> bool {
+        matches!(self, Self::Estimate(_))
+    }
+
+    /// The `is_missing` method returns `true` if the value is a code rather than a number.
+    pub fn is_missing(&self) -> bool {
+        self.number().is_none()
+    }
+
+    /// The `code` method returns the BEA code attached to the value, e.g. "(D)" or "E", or an
+    /// empty string for a plain number.
+    pub fn code(&self) -> &str {
+        match self {
+            Self::Number(_) => "",
+            Self::Estimate(_) => "E",
+            Self::Suppressed => "(D)",
+            Self::NotAvailable => "(NA)",
+            Self::NotMeaningful => "(NM)",
+            Self::Less => "(L)",
+            Self::Other(code) => code,
+        }
+    }
+
+    /// The `reason` method returns a description of the code attached to the value, or an empty
+    /// string for a plain number.
+    pub fn reason(&self) -> &str {
+        match self {
+            Self::Number(_) => "",
+            Self::Estimate(_) => "Estimate",
+            Self::Suppressed => "Suppressed to avoid disclosure of confidential information",
+            Self::NotAvailable => "Not available",
+            Self::NotMeaningful => "Not meaningful",
+            Self::Less => "Less than $50,000, included in totals",
+            Self::Other(_) => "Unrecognized BEA code",
+        }
+    }
+}
+
+impl Default for BeaValue {
+    fn default() -> Self {
+        Self::Number(0)
+    }
+}
+
+impl From<i64> for BeaValue {
+    fn from(value: i64) -> Self {
+        Self::Number(value)
+    }
+}
+
+impl fmt::Display for BeaValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Number(value) => write!(f, "{}", value),
+            Self::Estimate(value) => write!(f, "{} E", value),
+            _ => write!(f, "{}", self.code()),
+        }
+    }
+}
+
+impl std::str::FromStr for BeaValue {
+    type Err = Bandage;
+
+    /// Reads a value in the form BEA publishes, e.g. "1,234", "-56", "1,234 E" or "(D)".
+    fn from_str(input: &str) -> Clean<Self> {
+        let input = input.trim();
+        match input {
+            "(D)" => return Ok(Self::Suppressed),
+            "(NA)" => return Ok(Self::NotAvailable),
+            "(NM)" => return Ok(Self::NotMeaningful),
+            "(L)" => return Ok(Self::Less),
+            "" => return Err(Bandage::Hint("Missing BEA value.".to_string())),
+            _ => {}
+        }
+        match parse_number(input) {
+            Ok(("", (sign, digits, estimate))) => {
+                let mut value = digits.parse::<i64>()?;
+                if sign.is_some() {
+                    value = -value;
+                }
+                if estimate.is_some() {
+                    Ok(Self::Estimate(value))
+                } else {
+                    Ok(Self::Number(value))
+                }
+            }
+            _ if input.starts_with('(') && input.ends_with(')') => {
+                Ok(Self::Other(input.to_string()))
+            }
+            _ => Err(Bandage::Hint(format!("Invalid BEA value: {}", input))),
+        }
+    }
+}
+
+impl From<BeaValue> for String {
+    fn from(value: BeaValue) -> Self {
+        value.to_string()
+    }
+}
+
+impl TryFrom<String> for BeaValue {
+    type Error = Bandage;
+
+    fn try_from(value: String) -> Clean<Self> {
+        value.parse()
+    }
+}
+
+/// Splits a BEA number into the sign, the digits with thousands separators removed, and the
+/// estimate flag.  Called by [`BeaValue::from_str`].
+fn parse_number(input: &str) -> IResult<&str, (Option<&str>, String, Option<&str>)> {
+    let (rem, sign) = opt(tag("-"))(input)?;
+    let (rem, first) = digit1(rem)?;
+    let (rem, rest) = many0(preceded(tag(","), digit1))(rem)?;
+    let (rem, estimate) = opt(tag(" E"))(rem)?;
+    let mut digits = first.to_string();
+    rest.iter().for_each(|v| digits.push_str(v));
+    Ok((rem, (sign, digits, estimate)))
+}
@@ -1,5 +1,25 @@
+mod bea_analysis;
 mod bea_core;
-mod cainc5n_code_keys;
+mod bea_expression;
+mod bea_fips;
+mod bea_hierarchy;
+mod bea_measure;
+mod bea_region;
+mod bea_series;
+mod bea_table;
+mod bea_value;
 
-pub use bea_core::{BeaColumns, BeaData, BeaDataRaw, BeaDatum, BeaDatumRaw};
-// pub use cainc5n_code_keys::{deserialize_code_keys, Cainc5nCodeKey};
+pub use bea_analysis::{
+    BeaAnalysis, LocationQuotient, LocationQuotients, ShiftShare, ShiftShares, OREGON_FIPS, US_FIPS,
+};
+pub use bea_core::{BeaColumns, BeaData, BeaDataRaw, BeaDatum, BeaDatumRaw, BeaNormalized};
+pub use bea_expression::{BeaDerived, BeaExpression};
+pub use bea_fips::{FipsArea, FipsCatalog, FipsKind};
+pub use bea_hierarchy::{
+    BeaCheck, BeaCheckStatus, BeaChecks, BeaHierarchy, BeaLine, BeaLines, BeaSum,
+};
+pub use bea_measure::BeaMeasure;
+pub use bea_region::{BeaRegion, BeaRegions, RegionMissing, REGION_FIPS};
+pub use bea_series::{BeaPivot, BeaSeries, Deflator, DeflatorRow};
+pub use bea_table::BeaTable;
+pub use bea_value::BeaValue;
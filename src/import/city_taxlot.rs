@@ -1,10 +1,11 @@
 //! The `city_taxlot` submodule contains data structures associated with the city version of the
 //! county tax parcel GIS layer.
+use crate::import::taxlot::{impl_taxlot, read_shp, taxlot_from_shp};
+use crate::import::Taxlots;
 use crate::utils;
+use aid::prelude::Clean;
 use derive_more::{Deref, DerefMut};
-use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use tracing::warn;
 
 /// The `CityTaxlot` struct holds fields from the city version of the county tax parcel files.
 #[derive(
@@ -138,8 +139,32 @@ pub struct CityTaxlot {
     #[serde(rename = "Zone")]
     #[setters(doc = "Sets the value of the `zone` field.")]
     zone: String,
+    #[serde(skip)]
+    #[setters(doc = "Sets the value of the `geometry` field.")]
+    geometry: Option<geo::MultiPolygon>,
 }
 
+impl CityTaxlot {
+    /// Creates a new `CityTaxlot` from the polygon `geometry` and dBase `record` of a taxlot
+    /// shapefile.  The dBase field names are the column names of the CSV export, truncated to the
+    /// ten character limit of the dBase format.
+    pub fn from_shp(geometry: geo::MultiPolygon, record: &shapefile::dbase::Record) -> Clean<Self> {
+        taxlot_from_shp!(geometry, record, {
+            doc_link: utils::read_char(record, "Doc_Link").unwrap_or_default(),
+            latitude: utils::read_num(record, "Latitude").unwrap_or_default(),
+            longitude: utils::read_num(record, "Longitude").unwrap_or_default(),
+        })
+    }
+
+    /// The `csz_state` method returns the state of the owner mailing address.  The city layer has
+    /// no mailing state field, so it is read from the city, state and zip line.
+    pub fn csz_state(&self) -> &str {
+        utils::csz_state(&self.csz).unwrap_or_default()
+    }
+}
+
+impl_taxlot!(CityTaxlot, mailing_state = csz_state);
+
 /// The `CityTaxlots` struct contains a `records` field that holds a vector of type [`CityTaxlot`].
 #[derive(Debug, Clone, Default, Serialize, Deserialize, Deref, DerefMut)]
 pub struct CityTaxlots(Vec<CityTaxlot>);
@@ -151,50 +176,23 @@ impl CityTaxlots {
         Ok(CityTaxlots(records))
     }
 
-    /// The `addresses()` method returns the `address` field from each element of [`CityTaxlot`]
-    /// collected into a vector of type `String`.
-    pub fn addresses(&self) -> Vec<String> {
-        self.par_iter()
-            .map(|v| v.address())
-            .cloned()
-            .collect::<Vec<String>>()
+    /// Creates a new `CityTaxlots` struct from a taxlot polygon shapefile located at `path`.
+    /// Records that fail to import are logged and dropped.
+    pub fn from_shp<P: AsRef<std::path::Path>>(path: P) -> Clean<Self> {
+        Ok(CityTaxlots(read_shp(path, CityTaxlot::from_shp)?))
     }
+}
 
-    /// The `owner_names()` method returns the `owner_name` field from each element of [`CityTaxlot`]
-    /// collected into a vector of type `String`.
-    pub fn owner_names(&self) -> Vec<String> {
-        self.par_iter()
-            .map(|v| v.owner_name())
-            .cloned()
-            .collect::<Vec<String>>()
+impl From<Vec<CityTaxlot>> for CityTaxlots {
+    fn from(records: Vec<CityTaxlot>) -> Self {
+        Self(records)
     }
+}
 
-    /// Returns a vector of unique addresses associated with a given property owner `name`.
-    pub fn associated_addresses(&self, name: &str) -> Vec<String> {
-        let mut res = self
-            .par_iter()
-            .filter(|v| v.owner_name() == name)
-            .map(|v| v.address())
-            .cloned()
-            .collect::<Vec<String>>();
-        res.sort();
-        res.dedup();
-        if res.len() > 1 {
-            warn!("{} has {} associated addresses.", name, res.len());
-        }
-        res
-    }
+impl Taxlots for CityTaxlots {
+    type Item = CityTaxlot;
 
-    /// Returns a vector of unique owner names associated with a given address `address`.
-    pub fn associated_names(&self, address: &str) -> Vec<String> {
-        let mut res = self
-            .par_iter()
-            .filter(|v| v.address() == address)
-            .map(|v| v.owner_name())
-            .cloned()
-            .collect::<Vec<String>>();
-        res.sort();
-        res.dedup();
-        res
+    fn records(&self) -> &[CityTaxlot] {
+        &self.0
     }
 }
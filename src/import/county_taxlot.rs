@@ -1,10 +1,9 @@
+use crate::import::taxlot::{impl_taxlot, read_shp, taxlot_from_shp};
+use crate::import::{Taxlot, Taxlots};
 use crate::utils;
-use address::{Address, Addresses, MatchPartialRecords};
 use aid::prelude::Clean;
 use derive_more::{Deref, DerefMut};
-use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use tracing::warn;
 
 /// The `CountyTaxlot` struct holds data associated with Josephine County tax parcels.
 #[derive(
@@ -161,28 +160,52 @@ pub struct CountyTaxlot {
     #[serde(rename = "Zone")]
     #[setters(doc = "Sets the value of the `zone` field.")]
     zone: String,
+    #[serde(skip)]
+    #[setters(doc = "Sets the value of the `geometry` field.")]
+    geometry: Option<geo::MultiPolygon>,
 }
 
 impl CountyTaxlot {
-    /// Compare a taxlot situs address against an address record.
-    pub fn compare<
-        T: Address + Clone + Send + Sync + galileo::galileo_types::geo::GeoPoint<Num = f64>,
-        U: Addresses<T>,
-    >(
-        &self,
-        addresses: &U,
-    ) -> Clean<MatchPartialRecords> {
-        let (_, address) = address::Parser::address(self.situs())?;
-        let matches = address::MatchPartialRecord::compare(&address, addresses);
-        Ok(matches)
+    /// Creates a new `CountyTaxlot` from the polygon `geometry` and dBase `record` of a taxlot
+    /// shapefile.  The dBase field names are the column names of the CSV export, truncated to the
+    /// ten character limit of the dBase format.
+    pub fn from_shp(geometry: geo::MultiPolygon, record: &shapefile::dbase::Record) -> Clean<Self> {
+        taxlot_from_shp!(geometry, record, {
+            account_status: utils::read_char(record, "ACCTSTATUS").unwrap_or_default(),
+            city: utils::read_char(record, "City").unwrap_or_default(),
+            qq: utils::read_char(record, "QQ").unwrap_or_default(),
+            range: utils::read_num(record, "RNG").unwrap_or_default() as i32,
+            section: utils::read_num(record, "SEC").unwrap_or_default() as i32,
+            situs_street_name_suffix: utils::read_char(record, "SITUS_SUFF").unwrap_or_default(),
+            situs_street_name: utils::read_char(record, "ST_NAME").unwrap_or_default(),
+            situs_address_number: utils::read_char(record, "ST_NO").unwrap_or_default(),
+            mailing_state: utils::read_char(record, "State").unwrap_or_default(),
+            town: utils::read_num(record, "TWN").unwrap_or_default() as i32,
+            mailing_zip: utils::read_char(record, "ZIP").unwrap_or_default(),
+        })
+    }
+
+    /// The `check_map_number` method returns `true` if the township, range and section parsed from
+    /// the `map_number` field agree with the `town`, `range` and `section` fields.
+    pub fn check_map_number(&self) -> Clean<bool> {
+        let map = self.map_taxlot()?;
+        Ok(map.agrees(self.town, self.range, self.section))
     }
 }
 
+impl_taxlot!(CountyTaxlot);
+
 /// The `CountyTaxlots` struct holds a `records` field that contains a vector of type
 /// [`CountyTaxlot`].
 #[derive(Debug, Clone, Default, Serialize, Deserialize, Deref, DerefMut)]
 pub struct CountyTaxlots(Vec<CountyTaxlot>);
 
+impl From<Vec<CountyTaxlot>> for CountyTaxlots {
+    fn from(records: Vec<CountyTaxlot>) -> Self {
+        Self(records)
+    }
+}
+
 impl CountyTaxlots {
     /// Writes the contents of [`CountyTaxlots`] to a CSV file at the location specified in `path`.
     /// Each element in the vector of type [`CountyTaxlot`] maps to a row on the spreadsheet.
@@ -191,74 +214,17 @@ impl CountyTaxlots {
         Ok(CountyTaxlots(records))
     }
 
-    /// The `addresses()` method returns the `address` field from each element of [`CountyTaxlot`]
-    /// collected into a vector of type `String`.
-    pub fn addresses(&self) -> Vec<String> {
-        self.par_iter()
-            .map(|v| v.address())
-            .cloned()
-            .collect::<Vec<String>>()
-    }
-
-    /// The `owner_names()` method returns the `owner_name` field from each element of [`CountyTaxlot`]
-    /// collected into a vector of type `String`.
-    pub fn owner_names(&self) -> Vec<String> {
-        self.par_iter()
-            .map(|v| v.owner_name())
-            .cloned()
-            .collect::<Vec<String>>()
-    }
-
-    /// Returns a vector of unique addresses associated with a given property owner `name`.
-    pub fn associated_addresses(&self, name: &str) -> Vec<String> {
-        let mut res = self
-            .par_iter()
-            .filter(|v| v.owner_name() == name)
-            .map(|v| v.address())
-            .cloned()
-            .collect::<Vec<String>>();
-        res.sort();
-        res.dedup();
-        if res.len() > 1 {
-            warn!("{} has {} associated addresses.", name, res.len());
-        }
-        res
+    /// Creates a new `CountyTaxlots` struct from a taxlot polygon shapefile located at `path`.
+    /// Records that fail to import are logged and dropped.
+    pub fn from_shp<P: AsRef<std::path::Path>>(path: P) -> Clean<Self> {
+        Ok(CountyTaxlots(read_shp(path, CountyTaxlot::from_shp)?))
     }
+}
 
-    /// Returns a vector of unique owner names associated with a given address `address`.
-    pub fn associated_names(&self, address: &str) -> Vec<String> {
-        let mut res = self
-            .par_iter()
-            .filter(|v| v.address() == address)
-            .map(|v| v.owner_name())
-            .cloned()
-            .collect::<Vec<String>>();
-        res.sort();
-        res.dedup();
-        res
-    }
+impl Taxlots for CountyTaxlots {
+    type Item = CountyTaxlot;
 
-    /// Compare taxlot situs addresses against an address record.
-    pub fn compare<
-        T: Address + Clone + Send + Sync + galileo::galileo_types::geo::GeoPoint<Num = f64>,
-        U: Addresses<T>,
-    >(
-        &self,
-        addresses: &U,
-    ) -> Clean<MatchPartialRecords> {
-        let mut results = Vec::new();
-        self.iter()
-            .map(|v| match v.compare(addresses) {
-                Ok(records) => results.append(&mut records.to_vec()),
-                Err(e) => warn!("No partial match obtained: {}", e.to_string()),
-            })
-            .for_each(drop);
-        // for lot in self.iter() {
-        //     let check = lot.compare(addresses)?;
-        //     for record in check.iter() {
-        //         results.push(record.clone());
-        //     }
-        // }
-        Ok(MatchPartialRecords::new(results))
+    fn records(&self) -> &[CountyTaxlot] {
+        &self.0
     }
 }
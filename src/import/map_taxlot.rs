@@ -0,0 +1,191 @@
+//! The `map_taxlot` module contains the [`MapTaxlot`] type, which parses Oregon map and taxlot
+//! identifiers such as "36-05-17-AB-01200" into their township, range, section, quarter section
+//! and taxlot components.
+use aid::prelude::*;
+use nom::{bytes, character, combinator};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The `MapTaxlotStyle` enum lists the formats used to write a map and taxlot identifier.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MapTaxlotStyle {
+    /// Zero-padded parts separated by dashes, e.g. "36-05-17-AB-01200".
+    #[default]
+    Dashed,
+    /// Zero-padded parts separated by spaces, e.g. "36 05 17 AB 01200".
+    Spaced,
+    /// Zero-padded parts with no separator, e.g. "360517AB01200".
+    Compact,
+}
+
+/// The `MapTaxlot` struct holds the parts of an Oregon map and taxlot identifier.  Fields are
+/// ordered so that sorting groups taxlots by township, range, section and quarter section.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    derive_getters::Getters,
+)]
+pub struct MapTaxlot {
+    township: u8,
+    township_direction: char,
+    range: u8,
+    range_direction: char,
+    section: u8,
+    quarter: Option<char>,
+    quarter_quarter: Option<char>,
+    taxlot: Option<u32>,
+}
+
+impl MapTaxlot {
+    /// The `parse` method reads a map and taxlot identifier from `input`, returning the remaining
+    /// text and the parsed `MapTaxlot`.  Parts may be zero-padded or not, and separated by
+    /// dashes, spaces, periods or nothing at all.  Township and range directions ("N" or "S", "E"
+    /// or "W") are optional and default to south and west.  The quarter sections and taxlot
+    /// number are optional, so a bare map number such as "36-05-17-AB" also parses.
+    pub fn parse(input: &str) -> Clean<(&str, Self)> {
+        // Strip preceding whitespace
+        let (rem, _) = character::complete::space0(input)?;
+        let (rem, township) = combinator::map_res(
+            bytes::complete::take_while_m_n(1, 2, |c: char| c.is_ascii_digit()),
+            str::parse,
+        )(rem)?;
+        let (rem, township_direction) = combinator::opt(character::complete::one_of("NSns"))(rem)?;
+        let (rem, _) = separator(rem)?;
+        let (rem, range) = combinator::map_res(
+            bytes::complete::take_while_m_n(1, 2, |c: char| c.is_ascii_digit()),
+            str::parse,
+        )(rem)?;
+        let (rem, range_direction) = combinator::opt(character::complete::one_of("EWew"))(rem)?;
+        let (rem, _) = separator(rem)?;
+        let (rem, section) = combinator::map_res(
+            bytes::complete::take_while_m_n(1, 2, |c: char| c.is_ascii_digit()),
+            str::parse,
+        )(rem)?;
+        let (rem, _) = separator(rem)?;
+        // Quarter and quarter-quarter sections are the letters A through D.
+        let (rem, quarters) =
+            bytes::complete::take_while_m_n(0, 2, |c: char| "ABCDabcd".contains(c))(rem)?;
+        let mut quarters = quarters.chars().map(|c| c.to_ascii_uppercase());
+        let quarter = quarters.next();
+        let quarter_quarter = quarters.next();
+        let (rem, _) = separator(rem)?;
+        let (rem, taxlot) =
+            combinator::opt(combinator::map_res(character::complete::digit1, str::parse))(rem)?;
+        let map_taxlot = Self {
+            township,
+            township_direction: township_direction.map_or('S', |c| c.to_ascii_uppercase()),
+            range,
+            range_direction: range_direction.map_or('W', |c| c.to_ascii_uppercase()),
+            section,
+            quarter,
+            quarter_quarter,
+            taxlot,
+        };
+        Ok((rem, map_taxlot))
+    }
+
+    /// The `format` method writes the identifier in the given `style`.  Quarter sections and the
+    /// taxlot number are omitted when absent.  Directions are written only for townships north
+    /// or ranges east, since south and west are implied.
+    pub fn format(&self, style: MapTaxlotStyle) -> String {
+        let direction = |c: char, implied: char| {
+            if c == implied {
+                String::new()
+            } else {
+                c.to_string()
+            }
+        };
+        let mut parts = vec![
+            format!(
+                "{:02}{}",
+                self.township,
+                direction(self.township_direction, 'S')
+            ),
+            format!("{:02}{}", self.range, direction(self.range_direction, 'W')),
+            format!("{:02}", self.section),
+        ];
+        let quarters = self
+            .quarter
+            .iter()
+            .chain(self.quarter_quarter.iter())
+            .collect::<String>();
+        if !quarters.is_empty() {
+            parts.push(quarters);
+        }
+        if let Some(taxlot) = self.taxlot {
+            parts.push(format!("{:05}", taxlot));
+        }
+        match style {
+            MapTaxlotStyle::Dashed => parts.join("-"),
+            MapTaxlotStyle::Spaced => parts.join(" "),
+            MapTaxlotStyle::Compact => parts.join(""),
+        }
+    }
+
+    /// The `map` method returns the identifier without the taxlot number, which is the map the
+    /// taxlot is drawn on.
+    pub fn map(&self) -> Self {
+        let mut map = self.clone();
+        map.taxlot = None;
+        map
+    }
+
+    /// The `agrees` method returns `true` if the township, range and section of the identifier
+    /// match the values provided, as recorded in separate columns of the taxlot layer.
+    pub fn agrees(&self, township: i32, range: i32, section: i32) -> bool {
+        self.township as i32 == township
+            && self.range as i32 == range
+            && self.section as i32 == section
+    }
+}
+
+impl Default for MapTaxlot {
+    fn default() -> Self {
+        Self {
+            township: 0,
+            township_direction: 'S',
+            range: 0,
+            range_direction: 'W',
+            section: 0,
+            quarter: None,
+            quarter_quarter: None,
+            taxlot: None,
+        }
+    }
+}
+
+impl fmt::Display for MapTaxlot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.format(MapTaxlotStyle::Dashed))
+    }
+}
+
+impl std::str::FromStr for MapTaxlot {
+    type Err = Bandage;
+
+    /// Parses the whole of `input` as a map and taxlot identifier.  Unlike [`MapTaxlot::parse`],
+    /// trailing text other than whitespace is an error.
+    fn from_str(input: &str) -> Clean<Self> {
+        let (rem, map_taxlot) = Self::parse(input)?;
+        if rem.trim().is_empty() {
+            Ok(map_taxlot)
+        } else {
+            Err(Bandage::Hint(format!(
+                "Unexpected text after map taxlot: {}",
+                rem
+            )))
+        }
+    }
+}
+
+/// Consumes any run of dashes, spaces or periods separating the parts of an identifier.
+fn separator(input: &str) -> nom::IResult<&str, &str> {
+    bytes::complete::take_while(|c: char| c == '-' || c == ' ' || c == '.')(input)
+}
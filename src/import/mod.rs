@@ -4,9 +4,28 @@ pub mod beehive;
 mod city_taxlot;
 mod county_taxlot;
 mod jc_survey;
+mod map_taxlot;
+mod survey;
+mod taxlot;
+mod taxlot_filter;
 pub mod utilities;
 
-pub use bea::{BeaColumns, BeaData, BeaDataRaw, BeaDatum, BeaDatumRaw};
+pub use bea::{
+    BeaAnalysis, BeaCheck, BeaCheckStatus, BeaChecks, BeaColumns, BeaData, BeaDataRaw, BeaDatum,
+    BeaDatumRaw, BeaDerived, BeaExpression, BeaHierarchy, BeaLine, BeaLines, BeaMeasure,
+    BeaNormalized, BeaPivot, BeaRegion, BeaRegions, BeaSeries, BeaSum, BeaTable, BeaValue,
+    Deflator, DeflatorRow, FipsArea, FipsCatalog, FipsKind, LocationQuotient, LocationQuotients,
+    RegionMissing, ShiftShare, ShiftShares, OREGON_FIPS, REGION_FIPS, US_FIPS,
+};
 pub use city_taxlot::*;
 pub use county_taxlot::*;
-pub use jc_survey::*;
+#[allow(deprecated)]
+pub use jc_survey::JcSurvey;
+pub use map_taxlot::{MapTaxlot, MapTaxlotStyle};
+pub use survey::{
+    DuplicatePolicy, Survey, SurveyAudit, SurveyAuditItem, SurveyConfig, SurveyExport,
+    SurveyExportItem, SurveyOutcome, SurveyOutcomeItem, SurveyOutcomes, SurveyQuestion,
+    SurveyResponse,
+};
+pub use taxlot::{Taxlot, TaxlotGroup, Taxlots};
+pub use taxlot_filter::{TaxlotFilter, TaxlotQuery};
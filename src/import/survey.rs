@@ -0,0 +1,662 @@
+//! The `survey` module contains a configurable importer for survey responses.  A
+//! [`SurveyConfig`] declares the address column, the respondent city column and any number of
+//! categorical questions, so that each new survey only needs a new configuration.
+use crate::import::{Taxlot, Taxlots};
+use crate::utils;
+use address::{
+    MatchPartialRecord, MatchStatus, Parser, PartialAddress, PartialAddresses, SpatialAddresses,
+};
+use aid::prelude::*;
+use indicatif::ProgressBar;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+/// The `SurveyQuestion` struct declares a categorical question column and its allowed answers.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SurveyQuestion {
+    /// The name of the column holding the answers.
+    pub column: String,
+    /// The allowed answers.  Answers are compared ignoring case and surrounding whitespace, and an
+    /// empty list accepts any answer.
+    #[serde(default)]
+    pub answers: Vec<String>,
+}
+
+impl SurveyQuestion {
+    /// Creates a new `SurveyQuestion` for the column named `column` with allowed `answers`.
+    pub fn new(column: &str, answers: &[&str]) -> Self {
+        Self {
+            column: column.to_string(),
+            answers: answers.iter().map(|v| v.to_string()).collect(),
+        }
+    }
+
+    /// The `answer` method returns the allowed answer matching `input`, or an error if the
+    /// answer is not allowed.
+    pub fn answer(&self, input: &str) -> Clean<String> {
+        let input = input.trim();
+        if self.answers.is_empty() {
+            return Ok(input.to_string());
+        }
+        match self
+            .answers
+            .iter()
+            .find(|v| v.trim().eq_ignore_ascii_case(input))
+        {
+            Some(answer) => Ok(answer.clone()),
+            None => Err(Bandage::Hint(format!(
+                "Answer {} not allowed for {}.",
+                input, self.column
+            ))),
+        }
+    }
+}
+
+/// The `SurveyConfig` struct declares the columns of a survey CSV file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SurveyConfig {
+    /// The name of the column holding the physical address of the respondent.
+    pub address_column: String,
+    /// The name of the column holding the city of residence of the respondent, if any.
+    #[serde(default)]
+    pub city_column: Option<String>,
+    /// The categorical questions in the survey.
+    #[serde(default)]
+    pub questions: Vec<SurveyQuestion>,
+}
+
+impl SurveyConfig {
+    /// Creates a new `SurveyConfig` reading addresses from the column named `address_column`.
+    pub fn new(address_column: &str) -> Self {
+        Self {
+            address_column: address_column.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the name of the respondent city column.
+    pub fn with_city_column(mut self, column: &str) -> Self {
+        self.city_column = Some(column.to_string());
+        self
+    }
+
+    /// Adds `question` to the questions in the survey.
+    pub fn with_question(mut self, question: SurveyQuestion) -> Self {
+        self.questions.push(question);
+        self
+    }
+
+    /// Reads a `SurveyConfig` from the JSON file at location `path`.
+    pub fn from_json<P: AsRef<std::path::Path>>(path: P) -> Clean<Self> {
+        let file = std::fs::File::open(path)?;
+        let config = serde_json::from_reader(file).map_err(|e| Bandage::Hint(e.to_string()))?;
+        Ok(config)
+    }
+
+    /// The configuration of the 2023 public service revenue survey, with the respondent city in
+    /// the "City" column and the selected option in the "Option" column.
+    pub fn jc_survey() -> Self {
+        Self::new("Address")
+            .with_city_column("City")
+            .with_question(SurveyQuestion::new(
+                "Option",
+                &[
+                    "Option 1: $27/$88 Public Safety Utility Fee only",
+                    "Option 2: 7% Food & Beverage Tax only",
+                    "Option 3: 2% General Sales Tax only",
+                    "Option 4: Utility Fee/Sales Tax",
+                    "Option 5: Utility Fee/Food & Beverage Tax",
+                    "Option 6: Reduce Police/Fire Staffing",
+                ],
+            ))
+    }
+}
+
+/// The `SurveyResponse` struct represents a survey response where the submitted address text has
+/// been parsed into a [`PartialAddress`] object.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SurveyResponse {
+    /// The row number of the response, starting from one for the first row after the header.
+    pub row: usize,
+    /// The address text submitted by the respondent.
+    pub input: String,
+    /// The city of residence for the respondent.
+    pub city: Option<String>,
+    /// The [`PartialAddress`] object parsed from the submitted address text.
+    pub address: PartialAddress,
+    /// The answer to each question, keyed by the question column.
+    pub answers: BTreeMap<String, String>,
+}
+
+impl SurveyResponse {
+    /// Creates a new `SurveyResponse` from the fields of a CSV row.  The `index` function returns
+    /// the value of a named column in the row.  If the address does not parse or an answer is not
+    /// allowed, returns a [`SurveyOutcomeItem`] recording the failure.
+    fn new<'a, F: Fn(&str) -> Option<&'a str>>(
+        row: usize,
+        config: &SurveyConfig,
+        index: F,
+    ) -> Result<Self, SurveyOutcomeItem> {
+        let city = match &config.city_column {
+            Some(column) => index(column)
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty()),
+            None => None,
+        };
+        let raw_address = index(&config.address_column).unwrap_or_default();
+        let address = match Parser::address(&raw_address.to_uppercase()) {
+            Ok((_, addr)) => {
+                tracing::trace!("Parsed to {:#?}", &addr);
+                let mut address = addr.clone();
+                if let Some(ident) = addr.subaddress_identifier() {
+                    address.set_subaddress_identifier(&ident.to_uppercase())
+                };
+                address
+            }
+            Err(e) => {
+                return Err(SurveyOutcomeItem::rejected(
+                    row,
+                    raw_address,
+                    SurveyOutcome::ParseFailure,
+                    e.to_string(),
+                ))
+            }
+        };
+        let mut answers = BTreeMap::new();
+        for question in &config.questions {
+            match question.answer(index(&question.column).unwrap_or_default()) {
+                Ok(answer) => {
+                    answers.insert(question.column.clone(), answer);
+                }
+                Err(e) => {
+                    return Err(SurveyOutcomeItem::rejected(
+                        row,
+                        raw_address,
+                        SurveyOutcome::InvalidAnswer,
+                        e.to_string(),
+                    ))
+                }
+            }
+        }
+        Ok(Self {
+            row,
+            input: raw_address.to_string(),
+            city,
+            address,
+            answers,
+        })
+    }
+}
+
+/// The `Survey` struct holds the survey responses read using a [`SurveyConfig`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Survey {
+    /// The configuration used to read the survey.
+    pub config: SurveyConfig,
+    /// The `records` field holds a vector of [`SurveyResponse`] objects.
+    pub records: Vec<SurveyResponse>,
+    /// The responses that could not be read, with the reason for each.
+    #[serde(default)]
+    pub rejected: Vec<SurveyOutcomeItem>,
+}
+
+impl Survey {
+    /// Creates a new `Survey` from a CSV file located at `path`, using the columns declared in
+    /// `config`.  Returns an error if a declared column is missing from the file.  Malformed rows,
+    /// responses with an address that does not parse and responses with an answer that is not
+    /// allowed are kept in `rejected`.
+    pub fn from_csv<P: AsRef<std::path::Path>>(path: P, config: &SurveyConfig) -> Clean<Self> {
+        let file = std::fs::File::open(path)?;
+        let mut rdr = csv::Reader::from_reader(file);
+        let headers = rdr
+            .headers()
+            .map_err(|e| Bandage::Hint(e.to_string()))?
+            .iter()
+            .map(|v| v.trim().to_string())
+            .collect::<Vec<String>>();
+        let mut columns = vec![config.address_column.clone()];
+        columns.extend(config.city_column.clone());
+        columns.extend(config.questions.iter().map(|v| v.column.clone()));
+        if let Some(missing) = columns.iter().find(|v| !headers.contains(v)) {
+            return Err(Bandage::Hint(format!("Column {} not found.", missing)));
+        }
+        let rows = rdr
+            .records()
+            .collect::<Vec<Result<csv::StringRecord, csv::Error>>>();
+        let style = indicatif::ProgressStyle::with_template(
+            "[{elapsed_precise}] {bar:40.cyan/blue} {pos:>7}/{len:7} {'Parsing addresses.'}",
+        )
+        .unwrap();
+        let bar = ProgressBar::new(rows.len() as u64);
+        bar.set_style(style);
+        let mut records = Vec::new();
+        let mut rejected = Vec::new();
+        for (i, row) in rows.iter().enumerate() {
+            let row = match row {
+                Ok(row) => row,
+                Err(e) => {
+                    tracing::trace!("Rejecting row {}: {}", i + 1, e.to_string());
+                    rejected.push(SurveyOutcomeItem::rejected(
+                        i + 1,
+                        "",
+                        SurveyOutcome::MalformedRow,
+                        e.to_string(),
+                    ));
+                    bar.inc(1);
+                    continue;
+                }
+            };
+            let index = |column: &str| {
+                headers
+                    .iter()
+                    .position(|v| v == column)
+                    .and_then(|j| row.get(j))
+            };
+            match SurveyResponse::new(i + 1, config, index) {
+                Ok(record) => records.push(record),
+                Err(item) => {
+                    tracing::trace!("Rejecting row {}: {:?}", i + 1, item.error);
+                    rejected.push(item);
+                }
+            }
+            bar.inc(1);
+        }
+        tracing::info!("{} responses rejected.", rejected.len());
+        Ok(Self {
+            config: config.clone(),
+            records,
+            rejected,
+        })
+    }
+
+    /// Matches the [`PartialAddress`] of each response against the addresses in `other`.  The
+    /// method gathers complete and partial address matches into a [`SurveyExport`] struct.
+    pub fn validate(&self, other: &SpatialAddresses) -> SurveyExport {
+        let mut records = Vec::new();
+        for item in &self.records {
+            let res = MatchPartialRecord::compare(&item.address, other);
+            if let Some(record) = utils::best_match(&res) {
+                if let (MatchStatus::Matching | MatchStatus::Divergent, Some(x), Some(y)) =
+                    (record.match_status(), record.longitude(), record.latitude())
+                {
+                    records.push(SurveyExportItem {
+                        row: item.row,
+                        address: record.address_label(),
+                        answers: item.answers.clone(),
+                        weight: 1.,
+                        x_coordinate: x,
+                        y_coordinate: y,
+                    });
+                }
+            }
+        }
+        let questions = self
+            .config
+            .questions
+            .iter()
+            .map(|v| v.column.clone())
+            .collect();
+        SurveyExport { questions, records }
+    }
+
+    /// The `outcomes` method matches the [`PartialAddress`] of each response against the
+    /// addresses in `other`, and returns the outcome for every response in the survey file,
+    /// including the rejected responses, sorted by row.
+    pub fn outcomes(&self, other: &SpatialAddresses) -> SurveyOutcomes {
+        let mut records = self
+            .records
+            .iter()
+            .map(|v| SurveyOutcomeItem::matched(v.row, &v.input, &v.address, other))
+            .collect::<Vec<SurveyOutcomeItem>>();
+        records.extend(self.rejected.iter().cloned());
+        records.sort_by_key(|v| v.row);
+        SurveyOutcomes(records)
+    }
+}
+
+impl From<&Survey> for PartialAddresses {
+    fn from(data: &Survey) -> Self {
+        Self::new(data.records.iter().map(|v| v.address.clone()).collect())
+    }
+}
+
+/// The `SurveyOutcome` enum classifies what happened to a survey response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum SurveyOutcome {
+    /// The row could not be read from the survey file.
+    MalformedRow,
+    /// The address could not be parsed.
+    ParseFailure,
+    /// An answer was not one of the allowed answers.
+    InvalidAnswer,
+    /// The address parsed, but did not match an address in the record.
+    NoMatch,
+    /// The address matched an address in the record on some, but not all, elements.
+    PartialMatch,
+    /// The address matched an address in the record exactly.
+    ExactMatch,
+    /// The address matched an address in the record without coordinates, so the response is left
+    /// out of the [`SurveyExport`].
+    MissingCoordinates,
+}
+
+/// The `SurveyOutcomeItem` struct records the outcome of a single survey response, so that staff
+/// can correct the address and rerun the import.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SurveyOutcomeItem {
+    /// The row number of the response in the survey file.
+    pub row: usize,
+    /// The address text submitted by the respondent.
+    pub input: String,
+    /// The classification of the result.
+    pub outcome: SurveyOutcome,
+    /// The label of the matched address, for a partial or exact match.
+    pub address: Option<String>,
+    /// The x coordinate of the matched address.
+    pub x_coordinate: Option<f64>,
+    /// The y coordinate of the matched address.
+    pub y_coordinate: Option<f64>,
+    /// The reason the response was rejected.
+    pub error: Option<String>,
+}
+
+impl SurveyOutcomeItem {
+    /// Creates a new `SurveyOutcomeItem` for a response rejected with `outcome` because of
+    /// `error`.
+    pub fn rejected(row: usize, input: &str, outcome: SurveyOutcome, error: String) -> Self {
+        Self {
+            row,
+            input: input.to_string(),
+            outcome,
+            address: None,
+            x_coordinate: None,
+            y_coordinate: None,
+            error: Some(error),
+        }
+    }
+
+    /// Creates a new `SurveyOutcomeItem` by matching the parsed `address` against `other`.  The
+    /// outcome agrees with [`Survey::validate`], which exports only partial and exact matches with
+    /// coordinates.
+    pub fn matched(
+        row: usize,
+        input: &str,
+        address: &PartialAddress,
+        other: &SpatialAddresses,
+    ) -> Self {
+        let mut item = Self {
+            row,
+            input: input.to_string(),
+            outcome: SurveyOutcome::NoMatch,
+            address: None,
+            x_coordinate: None,
+            y_coordinate: None,
+            error: None,
+        };
+        let res = MatchPartialRecord::compare(address, other);
+        if let Some(record) = utils::best_match(&res) {
+            let outcome = match record.match_status() {
+                MatchStatus::Matching => SurveyOutcome::ExactMatch,
+                MatchStatus::Divergent => SurveyOutcome::PartialMatch,
+                _ => return item,
+            };
+            item.address = Some(record.address_label());
+            item.x_coordinate = record.longitude();
+            item.y_coordinate = record.latitude();
+            item.outcome = match (item.x_coordinate, item.y_coordinate) {
+                (Some(_), Some(_)) => outcome,
+                _ => SurveyOutcome::MissingCoordinates,
+            };
+        }
+        item
+    }
+}
+
+/// The `SurveyOutcomes` struct holds a vector of [`SurveyOutcomeItem`] objects, one for each
+/// response in the survey file.
+#[derive(
+    Debug, Clone, Default, PartialEq, Serialize, Deserialize, derive_more::Deref, derive_more::From,
+)]
+pub struct SurveyOutcomes(Vec<SurveyOutcomeItem>);
+
+impl SurveyOutcomes {
+    /// The `outcome` method returns the responses with outcome `outcome`.
+    pub fn outcome(&self, outcome: SurveyOutcome) -> Self {
+        Self(
+            self.iter()
+                .filter(|v| v.outcome == outcome)
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// The `counts` method returns the number of responses with each outcome.
+    pub fn counts(&self) -> BTreeMap<SurveyOutcome, usize> {
+        let mut counts = BTreeMap::new();
+        for item in self.iter() {
+            *counts.entry(item.outcome).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Write the contents of `SurveyOutcomes` to a CSV file at location `path`.  Each element in
+    /// the vector of type [`SurveyOutcomeItem`] maps to a row of data on the CSV.
+    pub fn to_csv<P: AsRef<std::path::Path>>(&mut self, path: P) -> Clean<()> {
+        utils::to_csv(&mut self.0, path)?;
+        Ok(())
+    }
+}
+
+/// The `SurveyExportItem` represents a survey response that has been partially or fully matched
+/// to a physical address, with coordinate data from the matched address.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SurveyExportItem {
+    /// The row number of the response in the survey file.
+    pub row: usize,
+    /// The address label of the matched address.
+    pub address: String,
+    /// The answer to each question, keyed by the question column.
+    pub answers: BTreeMap<String, String>,
+    /// The weight of the response when tallying results, set by [`SurveyExport::deduplicate`].
+    pub weight: f64,
+    /// The x coordinate for the spatial point assigned to the address.
+    pub x_coordinate: f64,
+    /// The y coordinate for the spatial point assigned to the address.
+    pub y_coordinate: f64,
+}
+
+/// The `SurveyExport` struct holds a vector of [`SurveyExportItem`] objects.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SurveyExport {
+    /// The question columns of the survey, in order.
+    pub questions: Vec<String>,
+    /// The `records` field holds a vector of [`SurveyExportItem`] objects.
+    pub records: Vec<SurveyExportItem>,
+}
+
+impl SurveyExport {
+    /// The `deduplicate` method applies `policy` to responses that matched the same address
+    /// label, returning the responses kept.
+    pub fn deduplicate(&self, policy: DuplicatePolicy) -> Self {
+        let mut groups: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+        for (i, item) in self.records.iter().enumerate() {
+            groups.entry(&item.address).or_default().push(i);
+        }
+        let mut keep = Vec::new();
+        for indices in groups.values() {
+            match policy {
+                DuplicatePolicy::KeepAll => keep.extend(indices.iter().map(|i| (*i, 1.))),
+                DuplicatePolicy::KeepFirst => keep.extend(indices.first().map(|i| (*i, 1.))),
+                DuplicatePolicy::KeepLast => keep.extend(indices.last().map(|i| (*i, 1.))),
+                DuplicatePolicy::Weighted => {
+                    let weight = 1. / indices.len() as f64;
+                    keep.extend(indices.iter().map(|i| (*i, weight)))
+                }
+            }
+        }
+        // Restore the order of responses in the survey file.
+        keep.sort_by_key(|v| v.0);
+        let records = keep
+            .iter()
+            .map(|(i, weight)| {
+                let mut item = self.records[*i].clone();
+                item.weight = *weight;
+                item
+            })
+            .collect::<Vec<SurveyExportItem>>();
+        tracing::info!(
+            "{} of {} responses kept.",
+            records.len(),
+            self.records.len()
+        );
+        Self {
+            questions: self.questions.clone(),
+            records,
+        }
+    }
+
+    /// Write the contents of `SurveyExport` to a CSV file at location `path`.  Each element in
+    /// the vector of type [`SurveyExportItem`] maps to a row of data on the CSV, with one column
+    /// for each question.
+    pub fn to_csv<P: AsRef<std::path::Path>>(&self, path: P) -> Clean<()> {
+        let mut wtr = csv::Writer::from_writer(std::fs::File::create(path)?);
+        let mut header = vec![
+            "row".to_string(),
+            "address".to_string(),
+            "x_coordinate".to_string(),
+            "y_coordinate".to_string(),
+            "weight".to_string(),
+        ];
+        header.extend(self.questions.clone());
+        wtr.write_record(&header)
+            .map_err(|e| Bandage::Hint(e.to_string()))?;
+        for item in &self.records {
+            let mut record = vec![
+                item.row.to_string(),
+                item.address.clone(),
+                item.x_coordinate.to_string(),
+                item.y_coordinate.to_string(),
+                item.weight.to_string(),
+            ];
+            for question in &self.questions {
+                record.push(item.answers.get(question).cloned().unwrap_or_default());
+            }
+            wtr.write_record(&record)
+                .map_err(|e| Bandage::Hint(e.to_string()))?;
+        }
+        wtr.flush()?;
+        Ok(())
+    }
+}
+
+/// The `DuplicatePolicy` enum sets how [`SurveyExport::deduplicate`] treats multiple responses
+/// matched to the same address.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DuplicatePolicy {
+    /// Keep every response with a weight of one.
+    #[default]
+    KeepAll,
+    /// Keep the first response from each address.
+    KeepFirst,
+    /// Keep the last response from each address.
+    KeepLast,
+    /// Keep every response, weighting each by one over the number of responses from the address.
+    Weighted,
+}
+
+impl std::str::FromStr for DuplicatePolicy {
+    type Err = Bandage;
+
+    /// Reads the policy from its name, e.g. "keep_first" or "weighted".
+    fn from_str(input: &str) -> Clean<Self> {
+        match input.to_lowercase().as_str() {
+            "keep_all" | "all" => Ok(Self::KeepAll),
+            "keep_first" | "first" => Ok(Self::KeepFirst),
+            "keep_last" | "last" => Ok(Self::KeepLast),
+            "weighted" | "weight" => Ok(Self::Weighted),
+            _ => Err(Bandage::Hint(format!(
+                "Invalid duplicate policy: {}",
+                input
+            ))),
+        }
+    }
+}
+
+/// The `SurveyAuditItem` struct flags a matched address with a suspicious number of responses, or
+/// a match to a non-residential property.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SurveyAuditItem {
+    /// The address label of the matched address.
+    pub address: String,
+    /// The number of responses matched to the address.
+    pub responses: usize,
+    /// Whether the number of responses exceeds the maximum expected from one household.
+    pub excess_responses: bool,
+    /// Whether the address is the situs of a non-residential taxlot.
+    pub non_residential: bool,
+}
+
+/// The `SurveyAudit` struct holds a vector of [`SurveyAuditItem`] objects, one for each flagged
+/// address.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, derive_more::Deref)]
+pub struct SurveyAudit(Vec<SurveyAuditItem>);
+
+impl SurveyAudit {
+    /// Creates a new `SurveyAudit` from the responses in `export`, before deduplication.  An
+    /// address is flagged if it has more than `max_responses` responses, or if its label is in
+    /// `non_residential`.  Flagged addresses are sorted by response count, highest
+    /// first.
+    pub fn new(
+        export: &SurveyExport,
+        max_responses: usize,
+        non_residential: &HashSet<String>,
+    ) -> Self {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for item in &export.records {
+            *counts.entry(&item.address).or_insert(0) += 1;
+        }
+        let mut records = counts
+            .iter()
+            .map(|(address, responses)| SurveyAuditItem {
+                address: address.to_string(),
+                responses: *responses,
+                excess_responses: *responses > max_responses,
+                non_residential: non_residential.contains(*address),
+            })
+            .filter(|v| v.excess_responses || v.non_residential)
+            .collect::<Vec<SurveyAuditItem>>();
+        records.sort_by(|a, b| b.responses.cmp(&a.responses));
+        Self(records)
+    }
+
+    /// The `non_residential` function returns the labels of the addresses in `addresses` matching
+    /// the situs of a taxlot in `parcels` with a non-residential property class.  The situs is
+    /// parsed and compared field by field against `addresses`, so the labels compare equal to the
+    /// matched labels in a [`SurveyExport`], and only exact matches are kept.  Property classes in
+    /// the 100s (residential), 400s to 600s (tract, farm and forest land, usually with a dwelling)
+    /// and 700s (multi-family) are residential, and taxlots without a property class are skipped.
+    pub fn non_residential<U: Taxlots>(
+        parcels: &U,
+        addresses: &SpatialAddresses,
+    ) -> HashSet<String> {
+        parcels
+            .records()
+            .iter()
+            .filter(|v| *v.property_class() > 0)
+            .filter(|v| !matches!(v.property_class() / 100, 1 | 4..=7))
+            .filter_map(|v| v.compare(addresses).ok())
+            .filter_map(|matches| {
+                utils::best_match(&matches)
+                    .filter(|v| v.match_status() == MatchStatus::Matching)
+                    .map(|v| v.address_label())
+            })
+            .collect()
+    }
+
+    /// Write the contents of `SurveyAudit` to a CSV file at location `path`.  Each element in the
+    /// vector of type [`SurveyAuditItem`] maps to a row of data on the CSV.
+    pub fn to_csv<P: AsRef<std::path::Path>>(&mut self, path: P) -> Clean<()> {
+        utils::to_csv(&mut self.0, path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,439 @@
+//! The `taxlot` module contains the [`Taxlot`] and [`Taxlots`] traits, which provide the accessors
+//! and collection methods common to the different versions of the tax parcel layer.
+use crate::import::{MapTaxlot, TaxlotQuery};
+use address::{Address, Addresses, MatchPartialRecords};
+use aid::prelude::{Bandage, Clean};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// The dBase field names used for the map number in exports of the taxlot layers.
+pub(crate) const MAP_NUMBER_FIELDS: &[&str] = &["MapNum", "MAPNUM", "MAP_NUM", "MAPTAXLOT"];
+
+/// The `Taxlot` trait provides access to the fields shared by the different sources of tax parcel
+/// data, such as [`crate::import::CountyTaxlot`] and [`crate::import::CityTaxlot`].  Methods that
+/// only need these fields can be written once against the trait.
+pub trait Taxlot {
+    /// The account number assigned to the taxlot by the county assessor.
+    fn account(&self) -> &String;
+    /// The first line of the owner mailing address.
+    fn address(&self) -> &String;
+    /// The city, state and zip code line of the owner mailing address.
+    fn csz(&self) -> &String;
+    /// The state of the owner mailing address.
+    fn mailing_state(&self) -> &str;
+    /// The name of the owner of record.
+    fn owner_name(&self) -> &String;
+    /// The situs address of the taxlot.
+    fn situs(&self) -> &String;
+    /// The map number of the taxlot.
+    fn map_number(&self) -> &String;
+    /// The property class code of the taxlot.
+    fn property_class(&self) -> &i32;
+    /// The zoning designation of the taxlot.
+    fn zone(&self) -> &String;
+    /// The appraisal neighborhood of the taxlot.
+    fn neighborhood(&self) -> &String;
+    /// The school district of the taxlot.
+    fn school_district(&self) -> &String;
+    /// The acreage of the taxlot computed from the GIS layer.
+    fn gis_acres(&self) -> &f64;
+    /// The acreage of the taxlot recorded by the assessor.
+    fn acreage(&self) -> &Option<f64>;
+    /// The square footage of the improvements on the taxlot.
+    fn square_feet(&self) -> &Option<f64>;
+    /// The appraised value of the taxlot.
+    fn appraised_value(&self) -> &Option<f64>;
+    /// The assessed value of the taxlot.
+    fn assessed_value(&self) -> &Option<f64>;
+    /// The real market value of the taxlot.
+    fn retail_market_value(&self) -> &Option<f64>;
+    /// The real market value of the land in the taxlot.
+    fn land_market(&self) -> &Option<f64>;
+    /// The value of the improvements on the taxlot.
+    fn imp_value(&self) -> &Option<f64>;
+    /// The property taxes levied on the taxlot.
+    fn taxes(&self) -> &Option<f64>;
+    /// The date of the most recent sale of the taxlot.
+    fn sale_date(&self) -> &Option<String>;
+    /// The price of the most recent sale of the taxlot.
+    fn sale_price(&self) -> &Option<f64>;
+    /// The type code of the most recent sale of the taxlot.
+    fn sale_type(&self) -> &Option<String>;
+    /// The polygon geometry of the taxlot, present when imported from a shapefile.
+    fn geometry(&self) -> &Option<geo::MultiPolygon>;
+
+    /// The `map_taxlot` method parses the `map_number` field into a [`MapTaxlot`], so that map
+    /// numbers written in different styles can be compared.
+    fn map_taxlot(&self) -> Clean<MapTaxlot> {
+        self.map_number().parse()
+    }
+
+    /// The `map_key` method returns the map number in canonical form, for joining taxlots whose
+    /// map numbers are written in different styles.  Returns `None` if the map number is blank
+    /// or does not parse, so that such taxlots do not join to each other.
+    fn map_key(&self) -> Option<String> {
+        self.map_taxlot().ok().map(|v| v.to_string())
+    }
+
+    /// Compare the situs address of the taxlot against an address record.
+    fn compare<
+        T: Address + Clone + Send + Sync + galileo::galileo_types::geo::GeoPoint<Num = f64>,
+        U: Addresses<T>,
+    >(
+        &self,
+        addresses: &U,
+    ) -> Clean<MatchPartialRecords> {
+        let (_, address) = address::Parser::address(self.situs())?;
+        let matches = address::MatchPartialRecord::compare(&address, addresses);
+        Ok(matches)
+    }
+}
+
+/// The `Taxlots` trait provides methods over a collection of type [`Taxlot`].
+pub trait Taxlots {
+    /// The type of [`Taxlot`] held in the collection.
+    type Item: Taxlot + Clone + Send + Sync;
+
+    /// The `records` method returns the taxlots in the collection as a slice.
+    fn records(&self) -> &[Self::Item];
+
+    /// The `addresses()` method returns the `address` field from each taxlot collected into a
+    /// vector of type `String`.
+    fn addresses(&self) -> Vec<String> {
+        self.records()
+            .par_iter()
+            .map(|v| v.address())
+            .cloned()
+            .collect::<Vec<String>>()
+    }
+
+    /// The `owner_names()` method returns the `owner_name` field from each taxlot collected into
+    /// a vector of type `String`.
+    fn owner_names(&self) -> Vec<String> {
+        self.records()
+            .par_iter()
+            .map(|v| v.owner_name())
+            .cloned()
+            .collect::<Vec<String>>()
+    }
+
+    /// Returns a vector of unique addresses associated with a given property owner `name`.
+    fn associated_addresses(&self, name: &str) -> Vec<String> {
+        let mut res = self
+            .records()
+            .par_iter()
+            .filter(|v| v.owner_name() == name)
+            .map(|v| v.address())
+            .cloned()
+            .collect::<Vec<String>>();
+        res.sort();
+        res.dedup();
+        if res.len() > 1 {
+            warn!("{} has {} associated addresses.", name, res.len());
+        }
+        res
+    }
+
+    /// Returns a vector of unique owner names associated with a given address `address`.
+    fn associated_names(&self, address: &str) -> Vec<String> {
+        let mut res = self
+            .records()
+            .par_iter()
+            .filter(|v| v.address() == address)
+            .map(|v| v.owner_name())
+            .cloned()
+            .collect::<Vec<String>>();
+        res.sort();
+        res.dedup();
+        res
+    }
+
+    /// The `query` method returns the taxlots that satisfy every filter in `query`.
+    fn query(&self, query: &TaxlotQuery) -> Self
+    where
+        Self: Sized + From<Vec<Self::Item>>,
+    {
+        let records = self
+            .records()
+            .iter()
+            .filter(|v| query.test(*v))
+            .cloned()
+            .collect::<Vec<Self::Item>>();
+        Self::from(records)
+    }
+
+    /// Compare taxlot situs addresses against an address record.
+    fn compare<
+        A: Address + Clone + Send + Sync + galileo::galileo_types::geo::GeoPoint<Num = f64>,
+        U: Addresses<A>,
+    >(
+        &self,
+        addresses: &U,
+    ) -> Clean<MatchPartialRecords> {
+        let mut results = Vec::new();
+        for lot in self.records() {
+            match lot.compare(addresses) {
+                Ok(records) => results.append(&mut records.to_vec()),
+                Err(e) => warn!("No partial match obtained: {}", e.to_string()),
+            }
+        }
+        Ok(MatchPartialRecords::new(results))
+    }
+}
+
+/// Reads the taxlots in the polygon shapefile at `path`, converting the geometry and dBase record
+/// of each shape with `read`.  Records that fail to import are logged and dropped.
+pub(crate) fn read_shp<T, P: AsRef<std::path::Path>>(
+    path: P,
+    read: fn(geo::MultiPolygon, &shapefile::dbase::Record) -> Clean<T>,
+) -> Clean<Vec<T>> {
+    let shp = shapefile::read(path)?;
+    let records = shp
+        .into_iter()
+        .filter_map(|(p, r)| match crate::utils::read_geo_multipolygon(p) {
+            Some(geometry) => match read(geometry, &r) {
+                Ok(lot) => Some(lot),
+                Err(e) => {
+                    warn!("Dropping taxlot: {}", e.to_string());
+                    None
+                }
+            },
+            None => {
+                warn!("Dropping taxlot: shape is not a polygon.");
+                None
+            }
+        })
+        .collect::<Vec<T>>();
+    Ok(records)
+}
+
+/// Builds a taxlot of type `Self` from the polygon `$geometry` and dBase `$record` of a taxlot
+/// shapefile, reading the fields common to the taxlot layers, followed by the `$field: $value`
+/// pairs specific to the layer.  The dBase field names are the column names of the CSV export,
+/// truncated to the ten character limit of the dBase format.  Returns an error if the record has
+/// no account number.
+macro_rules! taxlot_from_shp {
+    ($geometry:ident, $record:ident, { $($field:ident: $value:expr),* $(,)? }) => {
+        match $crate::utils::read_char($record, "ACCOUNT") {
+            Some(account) => Ok(Self {
+                account,
+                acreage: $crate::utils::read_num($record, "ACREAGE"),
+                addr1: $crate::utils::read_char($record, "ADDR1").unwrap_or_default(),
+                addr2: $crate::utils::read_char($record, "ADDR2"),
+                addr3: $crate::utils::read_char($record, "ADDR3"),
+                address: $crate::utils::read_char($record, "ADDRESS").unwrap_or_default(),
+                appraised_value: $crate::utils::read_num($record, "APPR_VALUE"),
+                assessed_value: $crate::utils::read_num($record, "ASSD_VALUE"),
+                bedrooms: $crate::utils::read_num($record, "BEDRMS"),
+                building_class: $crate::utils::read_char($record, "BLDG_CLASS"),
+                block: $crate::utils::read_char($record, "BLOCK"),
+                code: $crate::utils::read_char($record, "CODE").unwrap_or_default(),
+                comp_mtl: $crate::utils::read_char($record, "COMP_MTL"),
+                csz: $crate::utils::read_char($record, "CSZ").unwrap_or_default(),
+                deed_type: $crate::utils::read_char($record, "DEED_TYPE").unwrap_or_default(),
+                gis_acres: $crate::utils::read_num($record, "GIS_Acres").unwrap_or_default(),
+                imp_value: $crate::utils::read_num($record, "IMP_VALUE"),
+                instrument_number: $crate::utils::read_char($record, "INSTRUMENT"),
+                land_appraised: $crate::utils::read_num($record, "LAND_APPR"),
+                land_market: $crate::utils::read_num($record, "LAND_MKT"),
+                legal_acre: $crate::utils::read_num($record, "LEGAL_ACRE"),
+                living_area: $crate::utils::read_num($record, "LIVING_ARE"),
+                location_description: $crate::utils::read_char($record, "LOCATION_D"),
+                lot: $crate::utils::read_char($record, "LOT"),
+                lot_1: $crate::utils::read_char($record, "Lot_1"),
+                maint: $crate::utils::read_char($record, "MAINT").unwrap_or_default(),
+                map_number: $crate::utils::read_char_any(
+                    $record,
+                    $crate::import::taxlot::MAP_NUMBER_FIELDS,
+                )
+                .unwrap_or_default(),
+                mnx: $crate::utils::read_char($record, "MNX").unwrap_or_default(),
+                owner_name: $crate::utils::read_char($record, "NAME").unwrap_or_default(),
+                neighborhood: $crate::utils::read_char($record, "NBHD").unwrap_or_default(),
+                property_class: $crate::utils::read_num($record, "PROP_CLASS").unwrap_or_default()
+                    as i32,
+                retail_market_value: $crate::utils::read_num($record, "RMV"),
+                sale_date: $crate::utils::read_char($record, "SALE_DATE"),
+                sale_price: $crate::utils::read_num($record, "SALE_PRICE"),
+                sale_type: $crate::utils::read_char($record, "SALE_TYPE"),
+                school_district: $crate::utils::read_char($record, "SD").unwrap_or_default(),
+                situs: $crate::utils::read_char($record, "SITUS").unwrap_or_default(),
+                situs_city: $crate::utils::read_char($record, "SITUS_CITY").unwrap_or_default(),
+                situs_pref: $crate::utils::read_char($record, "SITUS_PREF"),
+                situs_state: $crate::utils::read_char($record, "SITUS_ST").unwrap_or_default(),
+                situs_suf0: $crate::utils::read_char($record, "SITUS_SUF0"),
+                situs_zip: $crate::utils::read_num($record, "SITUS_ZIP").unwrap_or_default() as i32,
+                sptb_codes: $crate::utils::read_char($record, "SPTB_CODES"),
+                square_feet: $crate::utils::read_num($record, "SQ_FT"),
+                taxes: $crate::utils::read_num($record, "Taxes"),
+                lot_type: $crate::utils::read_char($record, "TYPE").unwrap_or_default(),
+                year_built: $crate::utils::read_num($record, "YR_BLT"),
+                zone: $crate::utils::read_char($record, "Zone").unwrap_or_default(),
+                $($field: $value,)*
+                geometry: Some($geometry),
+            }),
+            None => {
+                tracing::warn!("Failed to read an account.");
+                Err(aid::prelude::Bandage::Hint(
+                    "Failed to read an account.".to_string(),
+                ))
+            }
+        }
+    };
+}
+pub(crate) use taxlot_from_shp;
+
+/// Implements [`Taxlot`] for a taxlot type `$lot` with the fields common to the taxlot layers.
+/// The mailing state is read from the `mailing_state` field, or from the method
+/// `$mailing_state` for layers without that field.
+macro_rules! impl_taxlot {
+    ($lot:ty) => {
+        $crate::import::taxlot::impl_taxlot!($lot, |lot| &lot.mailing_state);
+    };
+    ($lot:ty, mailing_state = $mailing_state:ident) => {
+        $crate::import::taxlot::impl_taxlot!($lot, |lot| lot.$mailing_state());
+    };
+    ($lot:ty, |$this:ident| $mailing_state:expr) => {
+        impl $crate::import::Taxlot for $lot {
+            fn account(&self) -> &String {
+                &self.account
+            }
+
+            fn address(&self) -> &String {
+                &self.address
+            }
+
+            fn csz(&self) -> &String {
+                &self.csz
+            }
+
+            fn mailing_state(&self) -> &str {
+                let $this = self;
+                $mailing_state
+            }
+
+            fn owner_name(&self) -> &String {
+                &self.owner_name
+            }
+
+            fn situs(&self) -> &String {
+                &self.situs
+            }
+
+            fn map_number(&self) -> &String {
+                &self.map_number
+            }
+
+            fn property_class(&self) -> &i32 {
+                &self.property_class
+            }
+
+            fn zone(&self) -> &String {
+                &self.zone
+            }
+
+            fn neighborhood(&self) -> &String {
+                &self.neighborhood
+            }
+
+            fn school_district(&self) -> &String {
+                &self.school_district
+            }
+
+            fn gis_acres(&self) -> &f64 {
+                &self.gis_acres
+            }
+
+            fn acreage(&self) -> &Option<f64> {
+                &self.acreage
+            }
+
+            fn square_feet(&self) -> &Option<f64> {
+                &self.square_feet
+            }
+
+            fn appraised_value(&self) -> &Option<f64> {
+                &self.appraised_value
+            }
+
+            fn assessed_value(&self) -> &Option<f64> {
+                &self.assessed_value
+            }
+
+            fn retail_market_value(&self) -> &Option<f64> {
+                &self.retail_market_value
+            }
+
+            fn land_market(&self) -> &Option<f64> {
+                &self.land_market
+            }
+
+            fn imp_value(&self) -> &Option<f64> {
+                &self.imp_value
+            }
+
+            fn taxes(&self) -> &Option<f64> {
+                &self.taxes
+            }
+
+            fn sale_date(&self) -> &Option<String> {
+                &self.sale_date
+            }
+
+            fn sale_price(&self) -> &Option<f64> {
+                &self.sale_price
+            }
+
+            fn sale_type(&self) -> &Option<String> {
+                &self.sale_type
+            }
+
+            fn geometry(&self) -> &Option<geo::MultiPolygon> {
+                &self.geometry
+            }
+        }
+    };
+}
+pub(crate) use impl_taxlot;
+
+/// The `TaxlotGroup` enum lists the fields used to group taxlots for summary statistics.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TaxlotGroup {
+    /// Group by the `zone` field.
+    #[default]
+    Zone,
+    /// Group by the `neighborhood` field.
+    Neighborhood,
+    /// Group by the `school_district` field.
+    SchoolDistrict,
+    /// Group by the `property_class` field.
+    PropertyClass,
+}
+
+impl TaxlotGroup {
+    /// The `key` method returns the value of the grouping field for `lot`.
+    pub fn key<T: Taxlot>(&self, lot: &T) -> String {
+        match self {
+            Self::Zone => lot.zone().clone(),
+            Self::Neighborhood => lot.neighborhood().clone(),
+            Self::SchoolDistrict => lot.school_district().clone(),
+            Self::PropertyClass => lot.property_class().to_string(),
+        }
+    }
+}
+
+impl std::str::FromStr for TaxlotGroup {
+    type Err = Bandage;
+
+    /// Reads the grouping field from its name, e.g. "zone" or "school_district".
+    fn from_str(input: &str) -> Clean<Self> {
+        match input.to_lowercase().as_str() {
+            "zone" => Ok(Self::Zone),
+            "neighborhood" | "nbhd" => Ok(Self::Neighborhood),
+            "school_district" | "sd" => Ok(Self::SchoolDistrict),
+            "property_class" | "prop_class" => Ok(Self::PropertyClass),
+            _ => Err(Bandage::Hint(format!("Invalid taxlot group: {}", input))),
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//! The `taxlot_filter` module contains predicates for selecting subsets of tax parcels, such as
+//! absentee owners or owners with a mailing address outside of the state.
+use crate::import::Taxlot;
+use crate::utils;
+use serde::{Deserialize, Serialize};
+
+/// The `TaxlotFilter` enum represents a single predicate applied to a [`Taxlot`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TaxlotFilter {
+    /// The mailing address of the owner matches the situs address of the taxlot.
+    OwnerOccupied,
+    /// The mailing address of the owner differs from the situs address of the taxlot.
+    Absentee,
+    /// The mailing state of the owner differs from the state code provided, e.g. "OR".
+    OutOfState(String),
+    /// The property class of the taxlot is one of the codes provided.
+    PropertyClass(Vec<i32>),
+}
+
+impl TaxlotFilter {
+    /// The `test` method returns `true` if `lot` satisfies the predicate.
+    pub fn test<T: Taxlot>(&self, lot: &T) -> bool {
+        match self {
+            Self::OwnerOccupied => owner_occupied(lot),
+            Self::Absentee => !owner_occupied(lot),
+            Self::OutOfState(state) => {
+                let mailing = lot.mailing_state().trim();
+                !mailing.is_empty() && !mailing.eq_ignore_ascii_case(state.trim())
+            }
+            Self::PropertyClass(classes) => classes.contains(lot.property_class()),
+        }
+    }
+}
+
+/// The `TaxlotQuery` struct combines any number of [`TaxlotFilter`] predicates.  A taxlot passes
+/// the query when it satisfies every filter.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TaxlotQuery {
+    filters: Vec<TaxlotFilter>,
+    min_properties: Option<usize>,
+}
+
+impl TaxlotQuery {
+    /// Creates an empty `TaxlotQuery`, which every taxlot passes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `filter` to the query and returns Self.
+    pub fn with(mut self, filter: TaxlotFilter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// Restricts mailing lists built under the query to owners with at least `count` properties.
+    pub fn with_min_properties(mut self, count: usize) -> Self {
+        self.min_properties = Some(count);
+        self
+    }
+
+    /// Returns a reference to the filters in the query.
+    pub fn filters(&self) -> &Vec<TaxlotFilter> {
+        &self.filters
+    }
+
+    /// Returns the minimum property count for owners, if any.
+    pub fn min_properties(&self) -> Option<usize> {
+        self.min_properties
+    }
+
+    /// The `test` method returns `true` if `lot` satisfies every filter in the query.
+    pub fn test<T: Taxlot>(&self, lot: &T) -> bool {
+        self.filters.iter().all(|v| v.test(lot))
+    }
+}
+
+/// The owner occupies the taxlot if the first line of the mailing address matches the situs
+/// address.
+fn owner_occupied<T: Taxlot>(lot: &T) -> bool {
+    utils::normalize(lot.address()) == utils::normalize(lot.situs())
+}
@@ -6,18 +6,41 @@
 #![doc(html_playground_url = "https://play.rust-lang.org/")]
 pub mod convert;
 pub mod data;
+pub mod geocode;
+pub mod geography;
 pub mod import;
+pub mod reconcile;
+pub mod sales;
+pub mod snapshot;
+pub mod stats;
 pub mod utils;
+pub mod valuation;
 
 /// The `prelude` module contains exports intended for user convenience.
 pub mod prelude {
     pub use crate::data::{
         ActiveLicense, ActiveLicenses, Business, BusinessInfo, Businesses, BusinessesInfo,
-        MailingList, MailingListExport, MailingListExportItem, MailingListItem,
+        MailingList, MailingListExport, MailingListExportItem, MailingListItem, MailingListRow,
+        MailingListRows,
     };
+    pub use crate::geocode::{CsvAddress, Geocodable, Geocode, Geocodes, MatchOutcome};
+    pub use crate::geography::{Area, Areas, CrossTab, CrossTabRow};
+    #[allow(deprecated)]
     pub use crate::import::{
-        BeaColumns, BeaData, BeaDataRaw, BeaDatum, BeaDatumRaw, CityTaxlot, CityTaxlots,
-        CountyTaxlot, CountyTaxlots, JcSurvey,
+        BeaAnalysis, BeaCheck, BeaCheckStatus, BeaChecks, BeaColumns, BeaData, BeaDataRaw,
+        BeaDatum, BeaDatumRaw, BeaDerived, BeaExpression, BeaHierarchy, BeaLine, BeaLines,
+        BeaMeasure, BeaNormalized, BeaPivot, BeaRegion, BeaRegions, BeaSeries, BeaTable, BeaValue,
+        CityTaxlot, CityTaxlots, CountyTaxlot, CountyTaxlots, Deflator, DuplicatePolicy, FipsArea,
+        FipsCatalog, FipsKind, JcSurvey, LocationQuotient, LocationQuotients, MapTaxlot,
+        MapTaxlotStyle, RegionMissing, ShiftShare, ShiftShares, Survey, SurveyAudit, SurveyConfig,
+        SurveyExport, SurveyExportItem, SurveyOutcome, SurveyOutcomes, SurveyQuestion, Taxlot,
+        TaxlotFilter, TaxlotGroup, TaxlotQuery, Taxlots, OREGON_FIPS, REGION_FIPS, US_FIPS,
     };
+    pub use crate::reconcile::{
+        ReconcileField, ReconcileItem, ReconcileOptions, ReconcileStatus, Reconciliation,
+    };
+    pub use crate::sales::{RatioStudy, RatioStudyItem, Sale, Sales};
+    pub use crate::snapshot::{TaxlotChange, TaxlotChangeKind, TaxlotChanges};
     pub use crate::utils::{from_csv, to_csv};
+    pub use crate::valuation::{ValuationSummaries, ValuationSummary};
 }
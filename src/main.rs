@@ -38,12 +38,331 @@ struct Cli {
         default_missing_value = "None"
     )]
     out: Option<std::path::PathBuf>,
+    #[arg(long, help = "Restrict parcels to owner-occupied taxlots.")]
+    owner_occupied: bool,
+    #[arg(long, help = "Restrict parcels to absentee owners.")]
+    absentee: bool,
+    #[arg(
+        long,
+        help = "Restrict parcels to owners with a mailing state other than this state code."
+    )]
+    out_of_state: Option<String>,
+    #[arg(
+        long,
+        help = "Restrict parcels to these property class codes, separated by commas.",
+        value_delimiter = ','
+    )]
+    property_class: Vec<i32>,
+    #[arg(
+        long,
+        help = "Restrict owners to those with at least this many properties."
+    )]
+    min_properties: Option<usize>,
+    #[arg(
+        long,
+        help = "Group summaries by zone, neighborhood, school_district or property_class."
+    )]
+    group_by: Option<String>,
+    #[arg(
+        long,
+        help = "Sale type codes for arm's-length sales, separated by commas.",
+        value_delimiter = ','
+    )]
+    sale_types: Vec<String>,
+    #[arg(
+        long,
+        help = "Fractional change in value to report, e.g. 0.1 for ten percent.",
+        default_value_t = 0.1
+    )]
+    threshold: f64,
+    #[arg(
+        long,
+        help = "Taxlot fields to reconcile, separated by commas, e.g. owner_name,market_value.",
+        value_delimiter = ','
+    )]
+    fields: Vec<String>,
+    #[arg(
+        long,
+        help = "Tolerance for numeric fields to reconcile, as FIELD=VALUE separated by commas.",
+        value_delimiter = ','
+    )]
+    tolerance: Vec<String>,
+    #[arg(
+        long,
+        help = "Read `source` as city taxlots rather than county taxlots."
+    )]
+    city: bool,
+    #[arg(long, help = "Name of the address column to geocode in a CSV file.")]
+    column: Option<String>,
+    #[arg(long, help = "Read `source` as business licenses.")]
+    businesses: bool,
+    #[arg(long, help = "Path to a JSON survey configuration.")]
+    config: Option<std::path::PathBuf>,
+    #[arg(
+        long,
+        help = "Duplicate survey response policy: keep_all (default), keep_first, keep_last or weighted."
+    )]
+    policy: Option<String>,
+    #[arg(
+        long,
+        help = "Largest number of survey responses expected from one address.",
+        default_value_t = 5
+    )]
+    max_responses: usize,
+    #[arg(
+        long,
+        help = "Path to county taxlots used to flag non-residential addresses."
+    )]
+    parcels: Option<std::path::PathBuf>,
+    #[arg(long, help = "Path to a shapefile or GeoJSON layer of polygon areas.")]
+    areas: Option<std::path::PathBuf>,
+    #[arg(
+        long,
+        help = "Name of the field holding the area names.",
+        default_value = "NAME"
+    )]
+    area_field: String,
+    #[arg(
+        long,
+        help = "Survey question to tabulate.  Defaults to the first question."
+    )]
+    question: Option<String>,
+    #[arg(long, help = "Path to write the outcome of every survey response.")]
+    report: Option<std::path::PathBuf>,
+    #[arg(
+        long,
+        help = "BEA tables to load, e.g. CAINC1,CAINC5N.  Defaults to CAINC5N.",
+        value_delimiter = ','
+    )]
+    tables: Vec<String>,
+    #[arg(
+        long,
+        help = "BEA line codes to select, separated by commas.",
+        value_delimiter = ','
+    )]
+    codes: Vec<String>,
+    #[arg(
+        long,
+        help = "FIPS numbers, area names, states or prefixes like 41* to select, separated by commas.",
+        value_delimiter = ','
+    )]
+    fips: Vec<String>,
+    #[arg(
+        long,
+        help = "Also select the codes replaced by or replacing each --fips number."
+    )]
+    lineage: bool,
+    #[arg(
+        long,
+        help = "Path to a CSV reference of FIPS numbers and area names, with fips and name columns."
+    )]
+    fips_reference: Option<std::path::PathBuf>,
+    #[arg(long, help = "Index each series to 100 in this year.")]
+    base_year: Option<i32>,
+    #[arg(long, help = "First year of the compound annual growth rate.")]
+    start_year: Option<i32>,
+    #[arg(long, help = "Last year of the compound annual growth rate.")]
+    end_year: Option<i32>,
+    #[arg(long, help = "Path to a CSV price index with year and value columns.")]
+    deflator: Option<std::path::PathBuf>,
+    #[arg(long, help = "Base year of constant dollars.")]
+    real_year: Option<i32>,
+    #[arg(
+        long,
+        help = "FIPS number of the benchmark area, e.g. 0 for the US or 41000 for Oregon.",
+        default_value_t = 0
+    )]
+    benchmark: i32,
+    #[arg(
+        long,
+        help = "BEA line code of the total industries are shares of.  Defaults to CAINC5N-35."
+    )]
+    total: Option<String>,
+    #[arg(
+        long,
+        help = "Derived BEA series of the form CODE = EXPRESSION, e.g. \"DERIVED-1 = CAINC5N-10 / CAEMP25N-10\"."
+    )]
+    derive: Vec<String>,
+    #[arg(
+        long,
+        help = "Path to a JSON file of regions and their member FIPS numbers."
+    )]
+    regions: Option<std::path::PathBuf>,
+    #[arg(
+        long,
+        help = "Policy for region members with missing values: suppress or partial."
+    )]
+    missing: Option<String>,
+    #[arg(
+        long,
+        help = "Path to write the selected BEA records with normalized units and whole-unit values."
+    )]
+    normalized: Option<std::path::PathBuf>,
+}
+
+impl Cli {
+    /// Builds a [`TaxlotQuery`] from the parcel filter options.
+    fn query(&self) -> TaxlotQuery {
+        let mut query = TaxlotQuery::new();
+        if self.owner_occupied {
+            query = query.with(TaxlotFilter::OwnerOccupied);
+        }
+        if self.absentee {
+            query = query.with(TaxlotFilter::Absentee);
+        }
+        if let Some(state) = &self.out_of_state {
+            query = query.with(TaxlotFilter::OutOfState(state.clone()));
+        }
+        if !self.property_class.is_empty() {
+            query = query.with(TaxlotFilter::PropertyClass(self.property_class.clone()));
+        }
+        if let Some(count) = self.min_properties {
+            query = query.with_min_properties(count);
+        }
+        query
+    }
+
+    /// Builds the [`FipsCatalog`] of the areas in `records`, starting from the reference at
+    /// --fips-reference if given.
+    fn fips_catalog(&self, records: &BeaData) -> Clean<FipsCatalog> {
+        let catalog = match &self.fips_reference {
+            Some(path) => FipsCatalog::from_csv(path)?,
+            None => FipsCatalog::new(),
+        };
+        Ok(catalog.with_data(records))
+    }
+
+    /// Builds the [`ReconcileOptions`] from the --fields and --tolerance options, comparing the
+    /// default fields if none are given.
+    fn reconcile_options(&self) -> Clean<ReconcileOptions> {
+        let mut options = if self.fields.is_empty() {
+            ReconcileOptions::default()
+        } else {
+            let fields = self
+                .fields
+                .iter()
+                .map(|v| v.parse())
+                .collect::<Clean<Vec<ReconcileField>>>()?;
+            ReconcileOptions::new(fields)
+        };
+        for tolerance in &self.tolerance {
+            match tolerance.split_once('=') {
+                Some((field, value)) => {
+                    let value = value
+                        .trim()
+                        .parse::<f64>()
+                        .map_err(|e| Bandage::Hint(e.to_string()))?;
+                    options = options.with_tolerance(field.parse()?, value);
+                }
+                None => {
+                    return Err(Bandage::Hint(format!(
+                        "Expected FIELD=VALUE, found {}",
+                        tolerance
+                    )))
+                }
+            }
+        }
+        Ok(options)
+    }
+
+    /// Reads the BEA tables named by --tables, defaulting to CAINC5N if none are given.
+    fn bea_tables(&self) -> Clean<Vec<BeaTable>> {
+        let mut tables = self
+            .tables
+            .iter()
+            .map(|v| v.parse::<BeaTable>())
+            .collect::<Clean<Vec<BeaTable>>>()?;
+        if tables.is_empty() {
+            tables.push(BeaTable::Cainc5n);
+        }
+        Ok(tables)
+    }
+}
+
+/// Reads county taxlots from a shapefile if `path` has a .shp extension, otherwise from a CSV.
+fn county_taxlots(path: std::path::PathBuf) -> Clean<CountyTaxlots> {
+    if path.extension().and_then(|v| v.to_str()) == Some("shp") {
+        CountyTaxlots::from_shp(path)
+    } else {
+        Ok(CountyTaxlots::from_csv(path)?)
+    }
+}
+
+/// Matches the situs of each taxlot in `records` against the city addresses at `path`, logging
+/// the counts by outcome and match status, and writing the report to `out` if provided.
+fn match_report<U: Taxlots>(
+    records: &U,
+    path: std::path::PathBuf,
+    out: Option<std::path::PathBuf>,
+) -> Clean<()>
+where
+    U::Item: Geocodable,
+{
+    info!("Records: {}", records.records().len());
+    let addresses = GrantsPassSpatialAddresses::from_csv(path)?;
+    let mut report = Geocodes::from_records(records.records(), &addresses);
+    info!("Outcomes: {:#?}", report.counts());
+    info!("Match status: {:#?}", report.status_counts());
+    if let Some(out) = out {
+        info!("Writing results to {out:?}.");
+        report.to_csv(out)?;
+    }
+    Ok(())
 }
 
 const CMD_HELP: &str = "
 Command to execute, including:
-* load_bea <PATH> -> Load BEA data stored locally on disk.
-* load_parcels <PATH> -> Load taxlots from a CSV.
+* bea_pivot <PATH> -> Pivot the BEA data at `source` to one row per location and line code with a
+  column for each year, selecting --codes <CODES> and --fips <FIPS>, adding the retired codes of
+  each area with --lineage.  Writes the values to
+  `target`, indexed to --base-year <YEAR> if given, with the growth rate from --start-year to
+  --end-year, and the year-over-year percent changes to `out`.  Converts dollar series to constant
+  dollars of --real-year <YEAR> using the price index at --deflator <PATH>.  Adds the series
+  defined by each --derive <DEFINITION> before selecting codes, after adding the regions defined
+  at --regions <PATH>, treating members with missing values by --missing <POLICY>.  Writes the
+  selected records with a normalized unit column and values in whole units to --normalized <PATH>.
+* bea_fips <PATH> -> Write the FIPS catalog of the BEA data at `source`, with the kind, parent and any
+  retirement of each area, to `target`.  Starts from the area names at --fips-reference <PATH> if
+  given, which also resolve --fips in bea_pivot and bea_shift_share.
+* bea_hierarchy <PATH> -> Read the line-code hierarchy of the first of --tables <TABLES> (CAINC5N by
+  default) from the BEA data at `source`, writing the lines with their parents to `target` and the
+  check that children sum to their parents to `out`.
+* bea_shift_share <PATH> -> Compare the industries of the area in --fips <FIPS> against the
+  --benchmark <FIPS> area in the BEA data at `source`, from --start-year to --end-year, as shares of
+  the --total <CODE> line.  Writes the location quotients to `target` and the shift-share table to
+  `out`.
+* compare <PATH> -> Match the situs of each taxlot at `source` against the city addresses at `target`,
+  writing the outcome for each taxlot to `out`.  Use --city to read city taxlots.
+* geocode <PATH> -> Geocode the situs of the taxlots at `source`, the business licenses at `source`
+  with --businesses, or the --column <NAME> of a CSV, against the city addresses at `data`, writing
+  coordinates and match outcome to `target`.
+* load_bea <PATH> -> Load BEA data stored locally on disk, from the raw download of each of --tables
+  <TABLES> named by the BEA_<TABLE>_RAW environmental variable, and write the records to `source`
+  and the records with a normalized unit column and values in whole units to `target`.
+* load_parcels <PATH> -> Load taxlots from a CSV or shapefile.  Writes the flat mailing list to `target`, and
+  optionally a structured mailing list to `out` (JSON for a .json extension, otherwise one row per
+  owner and parcel).  Filter the parcels with --owner-occupied, --absentee, --out-of-state <STATE>,
+  --property-class <CODES> and --min-properties <N>.
+* ratio_study <PATH> -> Write the sales ratio study by neighborhood and year to `target`, and the
+  sales to `out`.  Restrict to arm's-length sales with --sale-types <CODES>.
+* read_bea -> Read the processed CSV of each of --tables <TABLES> named by the BEA_<TABLE>_CSV
+  environmental variable, and log the lines present in each table, described by the line-code file
+  named by BEA_<TABLE>_LINES if set.
+* reconcile <PATH> -> Compare the city taxlots at `source` to the county taxlots at `data`, writing
+  taxlots missing from either side and mismatched --fields <FIELDS> to `target`.  Numeric fields
+  within --tolerance <FIELD=VALUE> are not reported.
+* survey <PATH> -> Read the survey responses at `source` using the JSON --config <PATH>, match the
+  addresses against the city addresses at `data` and write the matched responses to `target`,
+  deduplicated by --policy <POLICY>.  Writes addresses with over --max-responses <N> responses, or
+  matching non-residential taxlots from --parcels <PATH>, to `out`.  Writes the outcome of every
+  response, including unparseable addresses and invalid answers, to --report <PATH>.  Without
+  --config, reads the 2023 public service revenue survey, also available as jc_survey <PATH>.
+* survey_areas <PATH> -> Tabulate the answers to --question <NAME> in the survey at `source`, matched
+  against the city addresses at `data`, by the polygon --areas <PATH> named by --area-field <FIELD>.
+  Writes the cross-tab to `target` and the areas with tallies as GeoJSON to `out`.
+* taxlot_diff <PATH> -> Compare the previous taxlots at `source` to the current taxlots at `data`,
+  writing ownership, mailing address and value changes over --threshold to `target`.
+* valuation <PATH> -> Summarize taxlot values by --group-by <FIELD> and write the summary to `target`.
 ";
 
 fn main() -> Clean<()> {
@@ -53,31 +372,144 @@ fn main() -> Clean<()> {
     match cli.command.as_str() {
         "read_bea" => {
             dotenvy::dotenv().ok();
-            let path = std::env::var("BEA_CAINC5N_CSV")?;
-            let records = BeaData::from_csv(path)?;
+            let records = BeaData::from_tables(&cli.bea_tables()?)?;
             info!("Records: {}", records.len());
-            let hash = records.linecode_hash();
-            info!("Hash is {:#?}", hash);
+            for table in records.tables() {
+                info!("{} lines are {:#?}", table, records.line_codes(&table));
+            }
         }
         "load_bea" => {
             dotenvy::dotenv().ok();
-            let raw = std::env::var("BEA_CAINC5N_RAW")?;
-            info!("Reading raw csv file.");
-            let records = BeaDataRaw::from_csv(raw)?;
+            info!("Reading raw csv files.");
+            let records = BeaDataRaw::from_tables(&cli.bea_tables()?)?;
             info!("Converting raw csv data.");
             let mut records = BeaData::try_from(records)?;
             if let Some(path) = cli.source {
                 records.to_csv(path)?;
             }
+            if let Some(target) = cli.target {
+                records.to_normalized_csv(target)?;
+            }
             info!("Records: {}", records.len());
         }
+        "bea_pivot" => {
+            if let Some(path) = &cli.source {
+                let derived = cli
+                    .derive
+                    .iter()
+                    .map(|v| v.parse::<BeaDerived>())
+                    .collect::<Clean<Vec<BeaDerived>>>()?;
+                let mut records = BeaData::from_csv(path)?;
+                if let Some(regions) = &cli.regions {
+                    let missing = match &cli.missing {
+                        Some(policy) => policy.parse::<RegionMissing>()?,
+                        None => RegionMissing::default(),
+                    };
+                    records = records.with_regions(&BeaRegions::from_json(regions)?, missing)?;
+                }
+                let mut records = records.with_derived(&derived)?;
+                if !cli.codes.is_empty() {
+                    records = records.filter_many("code", &cli.codes);
+                }
+                if !cli.fips.is_empty() {
+                    let catalog = cli.fips_catalog(&records)?;
+                    records = records.filter_areas(&catalog, &cli.fips, cli.lineage);
+                }
+                if let Some(normalized) = &cli.normalized {
+                    records.to_normalized_csv(normalized)?;
+                }
+                let mut pivot = records.pivot();
+                if let (Some(deflator), Some(year)) = (&cli.deflator, cli.real_year) {
+                    let name = deflator
+                        .file_stem()
+                        .and_then(|v| v.to_str())
+                        .unwrap_or("deflator");
+                    pivot = pivot.deflate(&Deflator::from_csv(deflator, name)?, year)?;
+                }
+                if let Some(year) = cli.base_year {
+                    pivot = pivot.index(year);
+                }
+                info!("Series: {}", pivot.len());
+                let cagr = cli.start_year.zip(cli.end_year);
+                if let Some(target) = cli.target {
+                    pivot.to_csv(&target, cagr)?;
+                }
+                if let Some(out) = cli.out {
+                    pivot.percent_changes().to_csv(&out, None)?;
+                }
+            }
+        }
+        "bea_fips" => {
+            if let Some(path) = &cli.source {
+                let records = BeaData::from_csv(path)?;
+                let catalog = cli.fips_catalog(&records)?;
+                info!("Areas: {}", catalog.areas().len());
+                if let Some(out) = cli.target {
+                    catalog.to_csv(out)?;
+                }
+            }
+        }
+        "bea_hierarchy" => {
+            if let Some(path) = cli.source {
+                let table = match cli.tables.first() {
+                    Some(table) => table.parse::<BeaTable>()?,
+                    None => BeaTable::Cainc5n,
+                };
+                let records = BeaData::from_csv(path)?;
+                let hierarchy = BeaHierarchy::new(&records, &table);
+                info!("Lines: {}", hierarchy.lines.len());
+                if let Some(out) = cli.target {
+                    hierarchy.tree().to_csv(out)?;
+                }
+                let mut checks = hierarchy.check(&records);
+                info!("Checks: {:#?}", checks.counts());
+                if let Some(out) = cli.out {
+                    checks.to_csv(out)?;
+                }
+            }
+        }
+        "bea_shift_share" => {
+            if let (Some(path), Some(start), Some(end)) =
+                (&cli.source, cli.start_year, cli.end_year)
+            {
+                let records = BeaData::from_csv(path)?;
+                let target = match cli.fips.first() {
+                    Some(fips) => cli.fips_catalog(&records)?.resolve_one(fips)?,
+                    None => return Err(Bandage::Hint("Missing --fips of the target.".into())),
+                };
+                let mut analysis = BeaAnalysis::new(target, cli.benchmark, start, end);
+                if let Some(total) = &cli.total {
+                    analysis = analysis.with_total(total);
+                }
+                let mut quotients = analysis.location_quotients(&records)?;
+                info!("Location quotients: {}", quotients.len());
+                if let Some(out) = cli.target {
+                    quotients.to_csv(out)?;
+                }
+                let mut shares = analysis.shift_share(&records)?;
+                info!("Shift-share industries: {}", shares.len());
+                if let Some(out) = cli.out {
+                    shares.to_csv(out)?;
+                }
+            }
+        }
         "load_parcels" => {
+            let query = cli.query();
             if let Some(path) = cli.source {
                 info!("Importing county taxlots.");
-                let records = CountyTaxlots::from_csv(path)?;
+                let records = county_taxlots(path)?;
                 info!("Records: {}", records.len());
-                let mail = MailingList::try_from(&records)?;
+                let mail = MailingList::from_query(&records, &query)?;
                 info!("Records processed: {}", mail.len());
+                if let Some(out) = cli.out {
+                    if out.extension().and_then(|v| v.to_str()) == Some("json") {
+                        mail.to_json(&out)?;
+                    } else {
+                        let mut rows = MailingListRows::from(&mail);
+                        rows.to_csv(&out)?;
+                    }
+                    info!("Structured mailing list output to {}", &out.display());
+                }
                 let mut mail = MailingListExport::from(&mail);
                 mail.sort_by_key("properties");
                 let mail: Vec<MailingListExportItem> = mail.iter().rev().cloned().collect();
@@ -88,34 +520,163 @@ fn main() -> Clean<()> {
                 }
             }
         }
-        "compare" => {
+        "valuation" => {
             if let Some(path) = cli.source {
                 info!("Importing county taxlots.");
-                let records = CountyTaxlots::from_csv(path)?;
-                info!("Records: {}", records.len());
+                let records = county_taxlots(path)?;
+                let group = match &cli.group_by {
+                    Some(group) => group.parse::<TaxlotGroup>()?,
+                    None => TaxlotGroup::default(),
+                };
+                let mut summary = ValuationSummaries::from_parcels(&records, group);
+                info!("Groups: {}", summary.len());
                 if let Some(target) = cli.target {
-                    let addresses = address::GrantsPassSpatialAddresses::from_csv(target)?;
-                    let mut matches = records.compare(&addresses)?;
-                    info!("Records: {:?}", matches.len());
-                    if let Some(out) = cli.out {
-                        info!("Writing results to {out:?}.");
-                        matches.to_csv(out)?;
-                    }
+                    summary.to_csv(&target)?;
+                    info!("Valuation summary output to {}", &target.display());
                 }
             }
         }
-        "jc_survey" => {
+        "ratio_study" => {
             if let Some(path) = cli.source {
-                let records = JcSurvey::from_csv(path)?;
+                info!("Importing county taxlots.");
+                let records = county_taxlots(path)?;
+                let mut sales = Sales::from_parcels(&records);
+                if !cli.sale_types.is_empty() {
+                    sales = sales.arms_length(&cli.sale_types);
+                    info!("Arm's-length sales: {}", sales.len());
+                }
+                if let Some(out) = cli.out {
+                    sales.to_csv(&out)?;
+                    info!("Sales output to {}", &out.display());
+                }
+                let mut study = RatioStudy::from(&sales);
+                if let Some(target) = cli.target {
+                    study.to_csv(&target)?;
+                    info!("Ratio study output to {}", &target.display());
+                }
+            }
+        }
+        "taxlot_diff" => {
+            if let (Some(previous), Some(current)) = (cli.source, cli.data) {
+                info!("Importing previous county taxlots.");
+                let previous = county_taxlots(previous)?;
+                info!("Importing current county taxlots.");
+                let current = county_taxlots(current)?;
+                let mut changes = TaxlotChanges::compare(&previous, &current, cli.threshold);
+                if let Some(target) = cli.target {
+                    changes.to_csv(&target)?;
+                    info!("Taxlot changes output to {}", &target.display());
+                }
+            }
+        }
+        "reconcile" => {
+            if let (Some(city), Some(county)) = (cli.source, cli.data) {
+                info!("Importing city taxlots.");
+                let city = CityTaxlots::from_csv(city)?;
+                info!("Importing county taxlots.");
+                let county = county_taxlots(county)?;
+                let mut report = Reconciliation::compare(&city, &county, &cli.reconcile_options()?);
+                info!("Differences by type: {:#?}", report.counts());
+                if let Some(target) = cli.target {
+                    report.to_csv(&target)?;
+                    info!("Reconciliation output to {}", &target.display());
+                }
+            }
+        }
+        "compare" => {
+            if let (Some(path), Some(target)) = (cli.source, cli.target) {
+                if cli.city {
+                    info!("Importing city taxlots.");
+                    match_report(&CityTaxlots::from_csv(path)?, target, cli.out)?;
+                } else {
+                    info!("Importing county taxlots.");
+                    match_report(&county_taxlots(path)?, target, cli.out)?;
+                }
+            }
+        }
+        "geocode" => {
+            if let (Some(path), Some(file)) = (cli.source, cli.data) {
+                let city = GrantsPassSpatialAddresses::from_csv(file)?;
+                let city = SpatialAddresses::from(&city[..]);
+                info!("City addresses: {}", city.len());
+                let mut geocodes = if let Some(column) = &cli.column {
+                    Geocodes::from_csv(path, column, &city)?
+                } else if cli.businesses {
+                    Geocodes::from_records(&Businesses::from_csv(path)?[..], &city)
+                } else if cli.city {
+                    Geocodes::from_records(CityTaxlots::from_csv(path)?.records(), &city)
+                } else {
+                    Geocodes::from_records(county_taxlots(path)?.records(), &city)
+                };
+                info!("Outcomes: {:#?}", geocodes.counts());
+                if let Some(target) = cli.target {
+                    geocodes.to_csv(target)?;
+                }
+            }
+        }
+        "survey" | "jc_survey" => {
+            if let (Some(path), Some(file)) = (cli.source, cli.data) {
+                let config = match &cli.config {
+                    Some(config) => SurveyConfig::from_json(config)?,
+                    None => SurveyConfig::jc_survey(),
+                };
+                let records = Survey::from_csv(path, &config)?;
                 info!("Survey records: {}", records.records.len());
-                if let Some(file) = cli.data {
-                    let city = GrantsPassSpatialAddresses::from_csv(file)?;
-                    let city = SpatialAddresses::from(&city[..]);
-                    info!("City addresses: {}", city.len());
-                    let mut matches = records.validate(&city);
-                    if let Some(target) = cli.target {
-                        matches.to_csv(target)?;
+                let city = GrantsPassSpatialAddresses::from_csv(file)?;
+                let city = SpatialAddresses::from(&city[..]);
+                info!("City addresses: {}", city.len());
+                let matches = records.validate(&city);
+                if let Some(report) = &cli.report {
+                    let mut outcomes = records.outcomes(&city);
+                    info!("Outcomes: {:#?}", outcomes.counts());
+                    outcomes.to_csv(report)?;
+                }
+                if let Some(out) = cli.out {
+                    let mut non_residential = std::collections::HashSet::new();
+                    if let Some(parcels) = &cli.parcels {
+                        non_residential =
+                            SurveyAudit::non_residential(&county_taxlots(parcels.clone())?, &city);
                     }
+                    let mut audit = SurveyAudit::new(&matches, cli.max_responses, &non_residential);
+                    info!("Flagged addresses: {}", audit.len());
+                    audit.to_csv(out)?;
+                }
+                let policy = match &cli.policy {
+                    Some(policy) => policy.parse()?,
+                    None => DuplicatePolicy::default(),
+                };
+                let matches = matches.deduplicate(policy);
+                if let Some(target) = cli.target {
+                    matches.to_csv(target)?;
+                }
+            }
+        }
+        "survey_areas" => {
+            if let (Some(path), Some(file), Some(layer)) = (cli.source, cli.data, &cli.areas) {
+                let config = match &cli.config {
+                    Some(config) => SurveyConfig::from_json(config)?,
+                    None => SurveyConfig::jc_survey(),
+                };
+                let records = Survey::from_csv(path, &config)?;
+                let city = GrantsPassSpatialAddresses::from_csv(file)?;
+                let city = SpatialAddresses::from(&city[..]);
+                let policy = match &cli.policy {
+                    Some(policy) => policy.parse()?,
+                    None => DuplicatePolicy::default(),
+                };
+                let matches = records.validate(&city).deduplicate(policy);
+                let question = match &cli.question {
+                    Some(question) => question.clone(),
+                    None => matches.questions.first().cloned().unwrap_or_default(),
+                };
+                let areas = Areas::from_path(layer, &cli.area_field)?;
+                let tab = CrossTab::new(&matches, &question, &areas);
+                info!("Areas with responses: {}", tab.rows.len());
+                if let Some(target) = cli.target {
+                    tab.to_csv(target)?;
+                }
+                if let Some(out) = cli.out {
+                    tab.geojson(&areas, out)?;
                 }
             }
         }
@@ -0,0 +1,301 @@
+//! The `reconcile` module compares the city copy of the taxlot layer against the county layer,
+//! reporting taxlots present on only one side and fields that disagree.
+use crate::import::{Taxlot, Taxlots};
+use crate::utils;
+use aid::prelude::*;
+use derive_more::{Deref, DerefMut};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// The `ReconcileField` enum lists the taxlot fields that can be compared between the city and
+/// county layers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ReconcileField {
+    /// The `owner_name` field.
+    OwnerName,
+    /// The `situs` field.
+    Situs,
+    /// The mailing address, from the `address` and `csz` fields.
+    MailingAddress,
+    /// The `zone` field.
+    Zone,
+    /// The `neighborhood` field.
+    Neighborhood,
+    /// The `property_class` field.
+    PropertyClass,
+    /// The `retail_market_value` field.
+    MarketValue,
+    /// The `assessed_value` field.
+    AssessedValue,
+    /// The `taxes` field.
+    Taxes,
+}
+
+impl std::str::FromStr for ReconcileField {
+    type Err = Bandage;
+
+    /// Reads the field from its name, e.g. "owner_name" or "market_value".
+    fn from_str(input: &str) -> Clean<Self> {
+        match input.trim().to_lowercase().as_str() {
+            "owner_name" | "owner" => Ok(Self::OwnerName),
+            "situs" => Ok(Self::Situs),
+            "mailing_address" | "mailing" => Ok(Self::MailingAddress),
+            "zone" => Ok(Self::Zone),
+            "neighborhood" => Ok(Self::Neighborhood),
+            "property_class" => Ok(Self::PropertyClass),
+            "market_value" | "retail_market_value" => Ok(Self::MarketValue),
+            "assessed_value" => Ok(Self::AssessedValue),
+            "taxes" => Ok(Self::Taxes),
+            _ => Err(Bandage::Hint(format!("Invalid reconcile field: {}", input))),
+        }
+    }
+}
+
+/// The `FieldValue` enum holds the value of a [`ReconcileField`] read from a taxlot.
+enum FieldValue {
+    Text(String),
+    Number(Option<f64>),
+}
+
+impl ReconcileField {
+    /// Reads the value of the field from `lot`.
+    fn value<T: Taxlot>(&self, lot: &T) -> FieldValue {
+        match self {
+            Self::OwnerName => FieldValue::Text(lot.owner_name().clone()),
+            Self::Situs => FieldValue::Text(lot.situs().clone()),
+            Self::MailingAddress => FieldValue::Text(format!("{} {}", lot.address(), lot.csz())),
+            Self::Zone => FieldValue::Text(lot.zone().clone()),
+            Self::Neighborhood => FieldValue::Text(lot.neighborhood().clone()),
+            Self::PropertyClass => FieldValue::Number(Some(*lot.property_class() as f64)),
+            Self::MarketValue => FieldValue::Number(*lot.retail_market_value()),
+            Self::AssessedValue => FieldValue::Number(*lot.assessed_value()),
+            Self::Taxes => FieldValue::Number(*lot.taxes()),
+        }
+    }
+}
+
+/// The `ReconcileOptions` struct sets the fields compared by [`Reconciliation::compare`] and the
+/// tolerance allowed for numeric fields.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReconcileOptions {
+    fields: Vec<ReconcileField>,
+    tolerances: HashMap<ReconcileField, f64>,
+}
+
+impl Default for ReconcileOptions {
+    /// Compares owner, situs, zone and values, with no tolerance for numeric differences.
+    fn default() -> Self {
+        Self {
+            fields: vec![
+                ReconcileField::OwnerName,
+                ReconcileField::Situs,
+                ReconcileField::Zone,
+                ReconcileField::MarketValue,
+                ReconcileField::AssessedValue,
+            ],
+            tolerances: HashMap::new(),
+        }
+    }
+}
+
+impl ReconcileOptions {
+    /// Creates a new `ReconcileOptions` comparing the fields in `fields`.
+    pub fn new(fields: Vec<ReconcileField>) -> Self {
+        Self {
+            fields,
+            tolerances: HashMap::new(),
+        }
+    }
+
+    /// Sets the largest absolute difference in `field` that is not reported as a mismatch.
+    pub fn with_tolerance(mut self, field: ReconcileField, tolerance: f64) -> Self {
+        self.tolerances.insert(field, tolerance);
+        self
+    }
+
+    /// Returns a reference to the fields compared.
+    pub fn fields(&self) -> &Vec<ReconcileField> {
+        &self.fields
+    }
+
+    /// Returns the tolerance for `field`, which is zero unless set.
+    pub fn tolerance(&self, field: ReconcileField) -> f64 {
+        self.tolerances.get(&field).copied().unwrap_or_default()
+    }
+}
+
+/// The `ReconcileStatus` enum classifies a difference between the city and county layers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ReconcileStatus {
+    /// The taxlot is present in the city layer but not the county layer.
+    CityOnly,
+    /// The taxlot is present in the county layer but not the city layer.
+    CountyOnly,
+    /// The taxlot is present in both layers, but the value of a field differs.
+    Mismatch,
+    /// The account appears on more than one taxlot in the city layer.  Reported for each of these
+    /// taxlots after the first, which is the one joined by account.
+    CityDuplicate,
+    /// The account appears on more than one taxlot in the county layer.  Reported for each of
+    /// these taxlots after the first, which is the one joined by account.
+    CountyDuplicate,
+}
+
+/// The `ReconcileItem` struct records a single difference between the city and county layers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReconcileItem {
+    /// The classification of the difference.
+    pub status: ReconcileStatus,
+    /// The account number of the taxlot.
+    pub account: String,
+    /// The map number of the taxlot, formatted as a [`crate::import::MapTaxlot`] when it parses.
+    pub map_number: String,
+    /// The field that differs, for a mismatch.
+    pub field: Option<ReconcileField>,
+    /// The value of the field in the city layer.
+    pub city_value: Option<String>,
+    /// The value of the field in the county layer.
+    pub county_value: Option<String>,
+}
+
+impl ReconcileItem {
+    fn new<T: Taxlot>(status: ReconcileStatus, lot: &T) -> Self {
+        Self {
+            status,
+            account: lot.account().trim().to_string(),
+            map_number: lot
+                .map_key()
+                .unwrap_or_else(|| lot.map_number().trim().to_string()),
+            field: None,
+            city_value: None,
+            county_value: None,
+        }
+    }
+}
+
+/// The `Reconciliation` struct holds a vector of type [`ReconcileItem`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Deref, DerefMut)]
+pub struct Reconciliation(Vec<ReconcileItem>);
+
+impl Reconciliation {
+    /// Joins the taxlots in `city` to the taxlots in `county` and reports the differences.
+    /// Taxlots are joined on account number, and taxlots left unmatched are then joined on the
+    /// normalized map number.  Taxlots with a blank account and a blank or unparseable map number
+    /// are reported as unmatched.  Accounts that appear on more than one taxlot in a layer are
+    /// reported as duplicates.  Text fields are compared ignoring case and spacing.
+    pub fn compare<U: Taxlots, V: Taxlots>(
+        city: &U,
+        county: &V,
+        options: &ReconcileOptions,
+    ) -> Self {
+        let mut records = duplicates(city.records(), ReconcileStatus::CityDuplicate);
+        records.append(&mut duplicates(
+            county.records(),
+            ReconcileStatus::CountyDuplicate,
+        ));
+        let mut by_account = HashMap::new();
+        let mut by_map = HashMap::new();
+        for (i, lot) in county.records().iter().enumerate() {
+            // Skip blank accounts, so that they do not join to each other.
+            if !lot.account().trim().is_empty() {
+                by_account
+                    .entry(lot.account().trim().to_string())
+                    .or_insert(i);
+            }
+            if let Some(key) = lot.map_key() {
+                by_map.entry(key).or_insert(i);
+            }
+        }
+        let mut matched = HashSet::new();
+        for lot in city.records() {
+            let found = by_account
+                .get(lot.account().trim())
+                .filter(|i| !matched.contains(*i))
+                .or_else(|| {
+                    lot.map_key()
+                        .and_then(|key| by_map.get(&key))
+                        .filter(|i| !matched.contains(*i))
+                })
+                .copied();
+            match found {
+                Some(i) => {
+                    matched.insert(i);
+                    records.append(&mut mismatches(lot, &county.records()[i], options));
+                }
+                None => records.push(ReconcileItem::new(ReconcileStatus::CityOnly, lot)),
+            }
+        }
+        for (i, lot) in county.records().iter().enumerate() {
+            if !matched.contains(&i) {
+                records.push(ReconcileItem::new(ReconcileStatus::CountyOnly, lot));
+            }
+        }
+        tracing::info!("Differences found: {}", records.len());
+        Self(records)
+    }
+
+    /// The `counts` method returns the number of differences of each status, and the number of
+    /// mismatches for each field.
+    pub fn counts(&self) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::new();
+        for item in self.iter() {
+            let key = match item.field {
+                Some(field) => format!("{:?}: {:?}", item.status, field),
+                None => format!("{:?}", item.status),
+            };
+            *counts.entry(key).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Write the contents of `Reconciliation` to a CSV file at location `path`.  Each element in
+    /// the vector of type [`ReconcileItem`] maps to a row of data on the CSV.
+    pub fn to_csv<P: AsRef<std::path::Path>>(&mut self, path: P) -> Clean<()> {
+        utils::to_csv(self, path)?;
+        Ok(())
+    }
+}
+
+/// Lists the taxlots in `lots` with the same account as an earlier taxlot, with status `status`.
+/// Blank accounts are not joined, so they are not duplicates.
+fn duplicates<T: Taxlot>(lots: &[T], status: ReconcileStatus) -> Vec<ReconcileItem> {
+    let mut seen = HashSet::new();
+    lots.iter()
+        .filter(|v| !v.account().trim().is_empty() && !seen.insert(v.account().trim().to_string()))
+        .map(|v| ReconcileItem::new(status, v))
+        .collect()
+}
+
+/// Lists the fields in `options` where `city` and `county` disagree.
+fn mismatches<T: Taxlot, U: Taxlot>(
+    city: &T,
+    county: &U,
+    options: &ReconcileOptions,
+) -> Vec<ReconcileItem> {
+    let mut records = Vec::new();
+    for field in options.fields() {
+        let (city_value, county_value, differs) = match (field.value(city), field.value(county)) {
+            (FieldValue::Text(a), FieldValue::Text(b)) => {
+                let differs = utils::normalize(&a) != utils::normalize(&b);
+                (Some(a), Some(b), differs)
+            }
+            (FieldValue::Number(a), FieldValue::Number(b)) => {
+                let differs = match (a, b) {
+                    (Some(x), Some(y)) => (x - y).abs() > options.tolerance(*field),
+                    (None, None) => false,
+                    _ => true,
+                };
+                (a.map(|v| v.to_string()), b.map(|v| v.to_string()), differs)
+            }
+            _ => (None, None, true),
+        };
+        if differs {
+            let mut item = ReconcileItem::new(ReconcileStatus::Mismatch, county);
+            item.field = Some(*field);
+            item.city_value = city_value;
+            item.county_value = county_value;
+            records.push(item);
+        }
+    }
+    records
+}
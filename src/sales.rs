@@ -0,0 +1,203 @@
+//! The `sales` module contains typed sale records read from the sale fields of taxlots, and a
+//! sales ratio study comparing real market value against sale price.
+use crate::import::{Taxlot, Taxlots};
+use crate::stats;
+use crate::utils;
+use aid::prelude::*;
+use derive_more::{Deref, DerefMut};
+use jiff::civil;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The `Sale` struct holds the most recent sale of a taxlot, with the sale date parsed into a
+/// [`civil::Date`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, derive_getters::Getters)]
+pub struct Sale {
+    account: String,
+    map_number: String,
+    neighborhood: String,
+    date: civil::Date,
+    price: f64,
+    sale_type: Option<String>,
+    market_value: Option<f64>,
+}
+
+impl Sale {
+    /// Creates a new `Sale` from the sale fields of `lot`.  Returns `None` if the taxlot has no
+    /// sale date or sale price, or if the sale date does not parse.
+    pub fn from_taxlot<T: Taxlot>(lot: &T) -> Option<Self> {
+        let date = lot.sale_date().as_ref()?;
+        let price = (*lot.sale_price())?;
+        match utils::mdy(date) {
+            Ok((_, date)) => Some(Self {
+                account: lot.account().clone(),
+                map_number: lot.map_number().clone(),
+                neighborhood: lot.neighborhood().clone(),
+                date,
+                price,
+                sale_type: lot.sale_type().clone(),
+                market_value: *lot.retail_market_value(),
+            }),
+            Err(e) => {
+                tracing::trace!("Could not parse sale date {}: {}", date, e.to_string());
+                None
+            }
+        }
+    }
+
+    /// The `year` method returns the year of the sale.
+    pub fn year(&self) -> i16 {
+        self.date.year()
+    }
+
+    /// The `ratio` method returns the ratio of real market value to sale price.  Returns `None` if
+    /// the market value is missing or the sale price is not positive.
+    pub fn ratio(&self) -> Option<f64> {
+        match self.market_value {
+            Some(market) if self.price > 0. => Some(market / self.price),
+            _ => None,
+        }
+    }
+}
+
+/// The `Sales` struct holds a vector of type [`Sale`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Deref, DerefMut)]
+pub struct Sales(Vec<Sale>);
+
+impl Sales {
+    /// Creates a new `Sales` from the taxlots in `parcels` that have a valid sale date and price.
+    pub fn from_parcels<U: Taxlots>(parcels: &U) -> Self {
+        let records = parcels
+            .records()
+            .iter()
+            .filter_map(Sale::from_taxlot)
+            .collect::<Vec<Sale>>();
+        tracing::info!(
+            "{} of {} taxlots have a sale.",
+            records.len(),
+            parcels.records().len()
+        );
+        Self(records)
+    }
+
+    /// The `arms_length` method returns the sales with a positive price and a sale type code in
+    /// `types`, which lists the codes the assessor uses for arm's-length transactions.
+    pub fn arms_length(&self, types: &[String]) -> Self {
+        let records = self
+            .iter()
+            .filter(|v| v.price > 0.)
+            .filter(|v| match &v.sale_type {
+                Some(kind) => types.contains(&kind.trim().to_string()),
+                None => false,
+            })
+            .cloned()
+            .collect::<Vec<Sale>>();
+        Self(records)
+    }
+
+    /// The `between` method returns the sales with a sale date from `start` to `end` inclusive.
+    pub fn between(&self, start: civil::Date, end: civil::Date) -> Self {
+        let records = self
+            .iter()
+            .filter(|v| v.date >= start && v.date <= end)
+            .cloned()
+            .collect::<Vec<Sale>>();
+        Self(records)
+    }
+
+    /// Write the contents of `Sales` to a CSV file at location `path`.  Each element in the vector
+    /// of type [`Sale`] maps to a row of data on the CSV.
+    pub fn to_csv<P: AsRef<std::path::Path>>(&mut self, path: P) -> Clean<()> {
+        utils::to_csv(self, path)?;
+        Ok(())
+    }
+}
+
+/// The `RatioStudyItem` struct holds the sales ratio statistics for the sales in one neighborhood
+/// and year.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RatioStudyItem {
+    /// The appraisal neighborhood of the sales.
+    pub neighborhood: String,
+    /// The year of the sales.
+    pub year: i16,
+    /// The number of sales with a ratio.
+    pub count: usize,
+    /// The median ratio of real market value to sale price.
+    pub median_ratio: f64,
+    /// The mean ratio of real market value to sale price.
+    pub mean_ratio: f64,
+    /// The total real market value divided by the total sale price.
+    pub weighted_mean_ratio: f64,
+    /// The coefficient of dispersion, the average absolute deviation from the median ratio as a
+    /// percentage of the median ratio.  `None` if the median ratio is zero.
+    pub cod: Option<f64>,
+    /// The price-related differential, the mean ratio divided by the weighted mean ratio.  Values
+    /// above one indicate that lower priced properties are valued higher relative to their price.
+    /// `None` if the weighted mean ratio is zero.
+    pub prd: Option<f64>,
+}
+
+impl RatioStudyItem {
+    /// Creates a new `RatioStudyItem` from `sales`, labeled with `neighborhood` and `year`.
+    /// Returns `None` if none of the sales have a ratio.
+    pub fn new(neighborhood: &str, year: i16, sales: &[&Sale]) -> Option<Self> {
+        let pairs = sales
+            .iter()
+            .filter_map(|v| {
+                v.ratio()
+                    .map(|r| (r, v.market_value.unwrap_or_default(), v.price))
+            })
+            .collect::<Vec<(f64, f64, f64)>>();
+        let ratios = pairs.iter().map(|v| v.0).collect::<Vec<f64>>();
+        let median_ratio = stats::median(&ratios)?;
+        let mean_ratio = stats::mean(&ratios)?;
+        let market = pairs.iter().map(|v| v.1).sum::<f64>();
+        let price = pairs.iter().map(|v| v.2).sum::<f64>();
+        let weighted_mean_ratio = market / price;
+        let deviation =
+            ratios.iter().map(|v| (v - median_ratio).abs()).sum::<f64>() / ratios.len() as f64;
+        Some(Self {
+            neighborhood: neighborhood.to_string(),
+            year,
+            count: ratios.len(),
+            median_ratio,
+            mean_ratio,
+            weighted_mean_ratio,
+            cod: (median_ratio != 0.).then(|| 100. * deviation / median_ratio),
+            prd: (weighted_mean_ratio != 0.).then(|| mean_ratio / weighted_mean_ratio),
+        })
+    }
+}
+
+/// The `RatioStudy` struct holds a vector of type [`RatioStudyItem`], one for each neighborhood
+/// and year with sales.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Deref, DerefMut)]
+pub struct RatioStudy(Vec<RatioStudyItem>);
+
+impl RatioStudy {
+    /// Write the contents of `RatioStudy` to a CSV file at location `path`.  Each element in the
+    /// vector of type [`RatioStudyItem`] maps to a row of data on the CSV.
+    pub fn to_csv<P: AsRef<std::path::Path>>(&mut self, path: P) -> Clean<()> {
+        utils::to_csv(self, path)?;
+        Ok(())
+    }
+}
+
+impl From<&Sales> for RatioStudy {
+    /// Groups `sales` by neighborhood and year, sorted by neighborhood then year.
+    fn from(sales: &Sales) -> Self {
+        let mut groups: BTreeMap<(String, i16), Vec<&Sale>> = BTreeMap::new();
+        for sale in sales.iter() {
+            groups
+                .entry((sale.neighborhood.clone(), sale.year()))
+                .or_default()
+                .push(sale);
+        }
+        let records = groups
+            .iter()
+            .filter_map(|((neighborhood, year), v)| RatioStudyItem::new(neighborhood, *year, v))
+            .collect::<Vec<RatioStudyItem>>();
+        Self(records)
+    }
+}
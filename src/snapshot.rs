@@ -0,0 +1,220 @@
+//! The `snapshot` module compares two snapshots of the taxlot layer, such as consecutive quarterly
+//! releases from the county, and classifies the changes between them.
+use crate::import::{Taxlot, Taxlots};
+use crate::utils;
+use aid::prelude::*;
+use derive_more::{Deref, DerefMut};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The `TaxlotChangeKind` enum classifies a change between two taxlot snapshots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum TaxlotChangeKind {
+    /// The owner name changed, ignoring differences in case and spacing.
+    OwnershipTransfer,
+    /// The owner mailing address changed.
+    MailingAddressChange,
+    /// The real market or assessed value changed by more than the threshold.
+    ValueChange,
+    /// The account appears only in the current snapshot, usually from a partition or split.
+    NewAccount,
+    /// The account appears only in the previous snapshot, usually from a consolidation.
+    RetiredAccount,
+    /// The account appears on more than one taxlot in a snapshot.  Only the first of these
+    /// taxlots is compared against the other snapshot.
+    DuplicateAccount,
+}
+
+/// The `TaxlotChange` struct records a single change to a taxlot between two snapshots.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaxlotChange {
+    /// The classification of the change.
+    pub kind: TaxlotChangeKind,
+    /// The account number of the taxlot.
+    pub account: String,
+    /// The map number of the taxlot.
+    pub map_number: String,
+    /// The situs address of the taxlot.
+    pub situs: String,
+    /// The name of the field that changed, if the change concerns a single field.
+    pub field: Option<String>,
+    /// The value of the field in the previous snapshot.
+    pub previous: Option<String>,
+    /// The value of the field in the current snapshot.
+    pub current: Option<String>,
+}
+
+impl TaxlotChange {
+    /// Creates a new `TaxlotChange` for `lot` with no field values.
+    fn new<T: Taxlot>(kind: TaxlotChangeKind, lot: &T) -> Self {
+        Self {
+            kind,
+            account: lot.account().clone(),
+            map_number: lot.map_number().clone(),
+            situs: lot.situs().clone(),
+            field: None,
+            previous: None,
+            current: None,
+        }
+    }
+
+    /// Sets the field name and the previous and current values of the change.
+    fn with_values(mut self, field: &str, previous: String, current: String) -> Self {
+        self.field = Some(field.to_string());
+        self.previous = Some(previous);
+        self.current = Some(current);
+        self
+    }
+}
+
+/// The `TaxlotChanges` struct holds a vector of type [`TaxlotChange`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Deref, DerefMut)]
+pub struct TaxlotChanges(Vec<TaxlotChange>);
+
+impl TaxlotChanges {
+    /// Compares the taxlots in `previous` against the taxlots in `current`, keyed on the account
+    /// number, or the map number for taxlots without an account.  Taxlots with neither are
+    /// reported as retired or new, since they cannot be matched.  A value change is reported
+    /// when the real market or assessed value changes by more than `threshold`, expressed as a
+    /// fraction of the previous value, so 0.1 reports changes over ten percent.  An account
+    /// shared by several taxlots in either snapshot is reported once as a duplicate, with the
+    /// number of taxlots in the `previous` or `current` column.
+    pub fn compare<U: Taxlots, V: Taxlots>(previous: &U, current: &V, threshold: f64) -> Self {
+        let mut records = Vec::new();
+        let (before, unkeyed) = group(previous.records());
+        for lot in unkeyed {
+            records.push(TaxlotChange::new(TaxlotChangeKind::RetiredAccount, lot));
+        }
+        for lots in before.values().filter(|v| v.len() > 1) {
+            let mut change = TaxlotChange::new(TaxlotChangeKind::DuplicateAccount, lots[0]);
+            change.field = Some("account".to_string());
+            change.previous = Some(lots.len().to_string());
+            records.push(change);
+        }
+        let (after, unkeyed) = group(current.records());
+        for lot in unkeyed {
+            records.push(TaxlotChange::new(TaxlotChangeKind::NewAccount, lot));
+        }
+        for lots in after.values().filter(|v| v.len() > 1) {
+            let mut change = TaxlotChange::new(TaxlotChangeKind::DuplicateAccount, lots[0]);
+            change.field = Some("account".to_string());
+            change.current = Some(lots.len().to_string());
+            records.push(change);
+        }
+        let before = before
+            .into_iter()
+            .map(|(k, v)| (k, v[0]))
+            .collect::<BTreeMap<String, &U::Item>>();
+        let after = after
+            .into_iter()
+            .map(|(k, v)| (k, v[0]))
+            .collect::<BTreeMap<String, &V::Item>>();
+        for (id, old) in &before {
+            match after.get(id) {
+                Some(new) => records.append(&mut changes(*old, *new, threshold)),
+                None => records.push(TaxlotChange::new(TaxlotChangeKind::RetiredAccount, *old)),
+            }
+        }
+        for (id, new) in &after {
+            if !before.contains_key(id) {
+                records.push(TaxlotChange::new(TaxlotChangeKind::NewAccount, *new));
+            }
+        }
+        records.sort_by(|a, b| (&a.account, a.kind).cmp(&(&b.account, b.kind)));
+        tracing::info!("Changes found: {}", records.len());
+        Self(records)
+    }
+
+    /// The `kind` method returns the changes of type `kind`.
+    pub fn kind(&self, kind: TaxlotChangeKind) -> Self {
+        let records = self
+            .iter()
+            .filter(|v| v.kind == kind)
+            .cloned()
+            .collect::<Vec<TaxlotChange>>();
+        Self(records)
+    }
+
+    /// Write the contents of `TaxlotChanges` to a CSV file at location `path`.  Each element in
+    /// the vector of type [`TaxlotChange`] maps to a row of data on the CSV.
+    pub fn to_csv<P: AsRef<std::path::Path>>(&mut self, path: P) -> Clean<()> {
+        utils::to_csv(self, path)?;
+        Ok(())
+    }
+}
+
+/// Groups the taxlots in `lots` by the key used to match them across snapshots, the account
+/// number or else the canonical map number.  Also returns the taxlots with neither.
+fn group<T: Taxlot>(lots: &[T]) -> (BTreeMap<String, Vec<&T>>, Vec<&T>) {
+    let mut groups: BTreeMap<String, Vec<&T>> = BTreeMap::new();
+    let mut unkeyed = Vec::new();
+    for lot in lots {
+        let account = lot.account().trim();
+        let key = if account.is_empty() {
+            lot.map_key()
+        } else {
+            Some(account.to_string())
+        };
+        match key {
+            Some(key) => groups.entry(key).or_default().push(lot),
+            None => unkeyed.push(lot),
+        }
+    }
+    if !unkeyed.is_empty() {
+        tracing::warn!(
+            "{} taxlots without an account or map number.",
+            unkeyed.len()
+        );
+    }
+    (groups, unkeyed)
+}
+
+/// Lists the changes between the `old` and `new` versions of a taxlot.
+fn changes<T: Taxlot, U: Taxlot>(old: &T, new: &U, threshold: f64) -> Vec<TaxlotChange> {
+    let mut records = Vec::new();
+    if utils::normalize(old.owner_name()) != utils::normalize(new.owner_name()) {
+        records.push(
+            TaxlotChange::new(TaxlotChangeKind::OwnershipTransfer, new).with_values(
+                "owner_name",
+                old.owner_name().clone(),
+                new.owner_name().clone(),
+            ),
+        );
+    }
+    let old_mailing = format!("{}, {}", old.address().trim(), old.csz().trim());
+    let new_mailing = format!("{}, {}", new.address().trim(), new.csz().trim());
+    if utils::normalize(&old_mailing) != utils::normalize(&new_mailing) {
+        records.push(
+            TaxlotChange::new(TaxlotChangeKind::MailingAddressChange, new).with_values(
+                "mailing_address",
+                old_mailing,
+                new_mailing,
+            ),
+        );
+    }
+    for (field, before, after) in [
+        (
+            "retail_market_value",
+            *old.retail_market_value(),
+            *new.retail_market_value(),
+        ),
+        (
+            "assessed_value",
+            *old.assessed_value(),
+            *new.assessed_value(),
+        ),
+    ] {
+        if let (Some(before), Some(after)) = (before, after) {
+            if before > 0. && ((after - before) / before).abs() > threshold {
+                records.push(
+                    TaxlotChange::new(TaxlotChangeKind::ValueChange, new).with_values(
+                        field,
+                        before.to_string(),
+                        after.to_string(),
+                    ),
+                );
+            }
+        }
+    }
+    records
+}
@@ -0,0 +1,75 @@
+//! The `stats` module contains descriptive statistics shared by the analysis methods of the
+//! library, such as the median and quartiles of a set of values.
+use serde::{Deserialize, Serialize};
+
+/// The `Distribution` struct summarizes a set of values by count, mean and quartiles.  Quartiles
+/// use linear interpolation between the closest ranks.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    PartialOrd,
+    Serialize,
+    Deserialize,
+    derive_getters::Getters,
+)]
+pub struct Distribution {
+    count: usize,
+    mean: f64,
+    min: f64,
+    q1: f64,
+    median: f64,
+    q3: f64,
+    max: f64,
+}
+
+impl Distribution {
+    /// Creates a new `Distribution` from `values`, ignoring values that are not finite.  Returns
+    /// `None` if no finite values remain.
+    pub fn new(values: &[f64]) -> Option<Self> {
+        let mut sorted = values
+            .iter()
+            .copied()
+            .filter(|v| v.is_finite())
+            .collect::<Vec<f64>>();
+        if sorted.is_empty() {
+            return None;
+        }
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let count = sorted.len();
+        Some(Self {
+            count,
+            mean: sorted.iter().sum::<f64>() / count as f64,
+            min: sorted[0],
+            q1: quantile(&sorted, 0.25),
+            median: quantile(&sorted, 0.5),
+            q3: quantile(&sorted, 0.75),
+            max: sorted[count - 1],
+        })
+    }
+}
+
+/// The `quantile` function returns the value at quantile `q` (between 0 and 1) of the values in
+/// `sorted`, which must be sorted in ascending order and not empty.  Values between ranks are
+/// linearly interpolated.
+pub fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let position = q.clamp(0., 1.) * (sorted.len() - 1) as f64;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    let weight = position - lower as f64;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * weight
+}
+
+/// The `median` function returns the median of `values`, ignoring values that are not finite.
+/// Returns `None` if no finite values remain.
+pub fn median(values: &[f64]) -> Option<f64> {
+    Distribution::new(values).map(|v| v.median)
+}
+
+/// The `mean` function returns the arithmetic mean of `values`, ignoring values that are not
+/// finite.  Returns `None` if no finite values remain.
+pub fn mean(values: &[f64]) -> Option<f64> {
+    Distribution::new(values).map(|v| v.mean)
+}
@@ -1,6 +1,7 @@
 //! The `utils` module contains utility functions accessed by multiple data types, where declaring
 //! a stand-alone function eliminates code duplication in different methods.
 use crate::convert;
+use address::{MatchPartialRecord, MatchPartialRecords, MatchStatus};
 use jiff::civil;
 use nom::character::complete;
 use nom::{bytes, character, combinator};
@@ -61,6 +62,48 @@ pub fn from_csv<T: DeserializeOwned + Clone, P: AsRef<std::path::Path>>(
     Ok(records)
 }
 
+/// The `normalize` function reduces `text` to uppercase words separated by single spaces, so that
+/// differences in case and spacing do not prevent a match between text fields.
+pub fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .map(|v| v.to_uppercase())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// The `csz_state` function reads the state from a city, state and zip code line such as
+/// "GRANTS PASS, OR 97526-1234".  The line must end in a five or nine digit zip code preceded by a
+/// two-letter state, otherwise returns `None`, so that foreign addresses do not read as a state.
+pub fn csz_state(csz: &str) -> Option<&str> {
+    let mut words = csz.split_whitespace().rev();
+    let zip = words.next()?;
+    let digits = zip.chars().filter(|c| *c != '-').collect::<String>();
+    if !(digits.len() == 5 || digits.len() == 9) || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    // The city may be separated from the state by a comma without a space.
+    let state = words.next()?.rsplit(',').next()?;
+    if state.len() == 2 && state.chars().all(|c| c.is_ascii_alphabetic()) {
+        Some(state)
+    } else {
+        None
+    }
+}
+
+/// The `best_match` function returns the closest record in `matches`, preferring a full match,
+/// then a partial match, over the first record returned.
+pub fn best_match(matches: &MatchPartialRecords) -> Option<&MatchPartialRecord> {
+    matches
+        .iter()
+        .find(|v| v.match_status() == MatchStatus::Matching)
+        .or_else(|| {
+            matches
+                .iter()
+                .find(|v| v.match_status() == MatchStatus::Divergent)
+        })
+        .or_else(|| matches.first())
+}
+
 /// The `mdy` function is a helper that converts "\[month\]/\[day\]/\[year\]" format into its
 /// constituent parts and parses them to integers, for feeding into a datetime library (jiff).
 pub fn mdy(input: &str) -> aid::prelude::Clean<(&str, civil::Date)> {
@@ -151,6 +194,19 @@ pub fn read_geo_line(
     conv.into_geometry()
 }
 
+/// The `read_geo_multipolygon` method reads polygon geometry from a shapefile into a
+/// [`geo::geometry::MultiPolygon`] type.  Reads polygons with or without z values, and returns
+/// `None` for shapes that are not polygons.
+pub fn read_geo_multipolygon(shape: shapefile::Shape) -> Option<geo::geometry::MultiPolygon> {
+    // Convert from `shapefile` polygon rings to `geo` polygons.
+    let polygons = match shape {
+        shapefile::Shape::Polygon(polygon) => convert::Convert::new(polygon).geo_polygons(),
+        shapefile::Shape::PolygonZ(polygon) => convert::Convert::new(polygon).geo_polygons(),
+        _ => return None,
+    };
+    Some(geo::geometry::MultiPolygon::new(polygons))
+}
+
 /// The `read_geo_point` method reads the geometry of a shapefile into a [`geo::geometry::Geometry`]
 /// type.
 pub fn read_geo_point(point: &shapefile::PointZ) -> geo::geometry::Geometry {
@@ -184,6 +240,18 @@ pub fn read_char(record: &shapefile::dbase::Record, field: &str) -> Option<Strin
     result
 }
 
+/// The `read_char_any` function reads the first of `fields` present in `record`, for attributes
+/// whose field name differs between exports of the same layer.
+pub fn read_char_any(record: &shapefile::dbase::Record, fields: &[&str]) -> Option<String> {
+    match fields.iter().find(|v| record.get(v).is_some()) {
+        Some(field) => read_char(record, field),
+        None => {
+            tracing::warn!("Missing field: {}", fields.join(", "));
+            None
+        }
+    }
+}
+
 /// The `read_field` associated method is a helper function to extract field names from a
 /// [`shapefile::dbase::Record`].
 pub fn read_num(record: &shapefile::dbase::Record, field: &str) -> Option<f64> {
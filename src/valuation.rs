@@ -0,0 +1,174 @@
+//! The `valuation` module contains summary statistics of taxlot values, such as the distribution
+//! of real market and assessed values, the ratio of assessed to market value, and taxes per acre
+//! of land and per square foot of improvements, grouped by a [`TaxlotGroup`].
+use crate::import::{Taxlot, TaxlotGroup, Taxlots};
+use crate::stats::Distribution;
+use crate::utils;
+use aid::prelude::*;
+use derive_more::{Deref, DerefMut};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The `Valuation` struct holds the values of a single taxlot used in the summary statistics.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, derive_getters::Getters,
+)]
+pub struct Valuation {
+    /// The real market value of the taxlot.
+    market: Option<f64>,
+    /// The assessed value of the taxlot.
+    assessed: Option<f64>,
+    /// The property taxes levied on the taxlot.
+    taxes: Option<f64>,
+    /// The area of the taxlot in acres.  Uses the assessor acreage when present, otherwise the
+    /// GIS acreage.
+    acres: f64,
+    /// The square footage of the improvements on the taxlot.
+    square_feet: Option<f64>,
+}
+
+impl Valuation {
+    /// Creates a new `Valuation` from the fields of `lot`.
+    pub fn from_taxlot<T: Taxlot>(lot: &T) -> Self {
+        Self {
+            market: *lot.retail_market_value(),
+            assessed: *lot.assessed_value(),
+            taxes: *lot.taxes(),
+            acres: lot.acreage().unwrap_or(*lot.gis_acres()),
+            square_feet: *lot.square_feet(),
+        }
+    }
+
+    /// The `ratio` method returns the ratio of assessed value to real market value.  Under
+    /// Measure 50 the assessed value is capped below the market value, so the ratio measures
+    /// compression.  Returns `None` if either value is missing or the market value is zero.
+    pub fn ratio(&self) -> Option<f64> {
+        match (self.assessed, self.market) {
+            (Some(assessed), Some(market)) if market > 0. => Some(assessed / market),
+            _ => None,
+        }
+    }
+
+    /// The `taxes_per_acre` method returns the taxes levied per acre of land.
+    pub fn taxes_per_acre(&self) -> Option<f64> {
+        match self.taxes {
+            Some(taxes) if self.acres > 0. => Some(taxes / self.acres),
+            _ => None,
+        }
+    }
+
+    /// The `taxes_per_square_foot` method returns the taxes levied per square foot of
+    /// improvements.  Returns `None` for vacant land and taxlots missing the square footage.
+    pub fn taxes_per_square_foot(&self) -> Option<f64> {
+        match (self.taxes, self.square_feet) {
+            (Some(taxes), Some(square_feet)) if square_feet > 0. => Some(taxes / square_feet),
+            _ => None,
+        }
+    }
+}
+
+/// The `ValuationSummary` struct holds summary statistics for a group of taxlots.  Statistics are
+/// `None` when no taxlot in the group has the values required.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, derive_getters::Getters)]
+pub struct ValuationSummary {
+    /// The value of the grouping field shared by the taxlots.
+    group: String,
+    /// The number of taxlots in the group.
+    count: usize,
+    /// The mean real market value.
+    market_mean: Option<f64>,
+    /// The first quartile of real market value.
+    market_q1: Option<f64>,
+    /// The median real market value.
+    market_median: Option<f64>,
+    /// The third quartile of real market value.
+    market_q3: Option<f64>,
+    /// The mean assessed value.
+    assessed_mean: Option<f64>,
+    /// The first quartile of assessed value.
+    assessed_q1: Option<f64>,
+    /// The median assessed value.
+    assessed_median: Option<f64>,
+    /// The third quartile of assessed value.
+    assessed_q3: Option<f64>,
+    /// The median ratio of assessed to real market value.
+    ratio_median: Option<f64>,
+    /// The mean ratio of assessed to real market value.
+    ratio_mean: Option<f64>,
+    /// The total taxes levied on the group.
+    taxes_total: f64,
+    /// The median taxes per acre.
+    taxes_per_acre_median: Option<f64>,
+    /// The median taxes per square foot of improvements.
+    taxes_per_square_foot_median: Option<f64>,
+}
+
+impl ValuationSummary {
+    /// Creates a new `ValuationSummary` for the taxlot values in `values`, labeled `group`.
+    pub fn new(group: &str, values: &[Valuation]) -> Self {
+        let market = Distribution::new(&values.iter().filter_map(|v| v.market).collect::<Vec<_>>());
+        let assessed =
+            Distribution::new(&values.iter().filter_map(|v| v.assessed).collect::<Vec<_>>());
+        let ratio = Distribution::new(&values.iter().filter_map(|v| v.ratio()).collect::<Vec<_>>());
+        let per_acre = Distribution::new(
+            &values
+                .iter()
+                .filter_map(|v| v.taxes_per_acre())
+                .collect::<Vec<_>>(),
+        );
+        let per_square_foot = Distribution::new(
+            &values
+                .iter()
+                .filter_map(|v| v.taxes_per_square_foot())
+                .collect::<Vec<_>>(),
+        );
+        Self {
+            group: group.to_string(),
+            count: values.len(),
+            market_mean: market.map(|v| *v.mean()),
+            market_q1: market.map(|v| *v.q1()),
+            market_median: market.map(|v| *v.median()),
+            market_q3: market.map(|v| *v.q3()),
+            assessed_mean: assessed.map(|v| *v.mean()),
+            assessed_q1: assessed.map(|v| *v.q1()),
+            assessed_median: assessed.map(|v| *v.median()),
+            assessed_q3: assessed.map(|v| *v.q3()),
+            ratio_median: ratio.map(|v| *v.median()),
+            ratio_mean: ratio.map(|v| *v.mean()),
+            taxes_total: values.iter().filter_map(|v| v.taxes).sum(),
+            taxes_per_acre_median: per_acre.map(|v| *v.median()),
+            taxes_per_square_foot_median: per_square_foot.map(|v| *v.median()),
+        }
+    }
+}
+
+/// The `ValuationSummaries` struct holds a vector of type [`ValuationSummary`], one for each
+/// group of taxlots.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, Deref, DerefMut)]
+pub struct ValuationSummaries(Vec<ValuationSummary>);
+
+impl ValuationSummaries {
+    /// Creates a new `ValuationSummaries` from the taxlots in `parcels`, with one
+    /// [`ValuationSummary`] for each value of the `group` field, sorted by group.
+    pub fn from_parcels<U: Taxlots>(parcels: &U, group: TaxlotGroup) -> Self {
+        let mut groups: BTreeMap<String, Vec<Valuation>> = BTreeMap::new();
+        for lot in parcels.records() {
+            groups
+                .entry(group.key(lot))
+                .or_default()
+                .push(Valuation::from_taxlot(lot));
+        }
+        let records = groups
+            .iter()
+            .map(|(k, v)| ValuationSummary::new(k, v))
+            .collect::<Vec<ValuationSummary>>();
+        Self(records)
+    }
+
+    /// Write the contents of `ValuationSummaries` to a CSV file at location `path`.  Each element
+    /// in the vector of type [`ValuationSummary`] maps to a row of data on the CSV.
+    pub fn to_csv<P: AsRef<std::path::Path>>(&mut self, path: P) -> Clean<()> {
+        utils::to_csv(self, path)?;
+        Ok(())
+    }
+}
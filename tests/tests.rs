@@ -134,6 +134,81 @@ fn parcel_address_match() -> Clean<()> {
     Ok(())
 }
 
+// Matches the situs of three taxlots against the city addresses: city hall, a street that does
+// not exist and a blank situs.  Every taxlot is kept in the report, with one outcome each.
+#[test]
+fn geocode_taxlot_outcomes() -> Clean<()> {
+    let file_path = "../address/data/city_addresses_20241007.csv";
+    let addresses = address::SpatialAddresses::from(
+        &address::GrantsPassSpatialAddresses::from_csv(file_path)?[..],
+    );
+    let lots = CountyTaxlots::from(
+        ["101 NW A ST", "99999 NW NOWHERE AVE", ""]
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                CountyTaxlot::default()
+                    .with_account(i.to_string())
+                    .with_situs(v.to_string())
+            })
+            .collect::<Vec<CountyTaxlot>>(),
+    );
+    let report = Geocodes::from_records(lots.records(), &addresses);
+    assert_eq!(report.len(), 3);
+    assert_eq!(report[0].id, "0");
+    assert_eq!(report[0].outcome, MatchOutcome::Matched);
+    assert!(report[0].address_label.is_some());
+    assert!(report[0].x_coordinate.is_some());
+    assert_ne!(report[1].outcome, MatchOutcome::Matched);
+    assert_eq!(report[2].outcome, MatchOutcome::Unparseable);
+    assert!(report[2].error.is_some());
+    assert!(report[2].x_coordinate.is_none());
+    let counts = report.counts();
+    assert_eq!(counts.values().sum::<usize>(), 3);
+    assert_eq!(counts[&MatchOutcome::Unparseable], 1);
+    assert_eq!(report.outcome(MatchOutcome::Matched).len(), 1);
+    let status = report.status_counts();
+    assert_eq!(status.values().sum::<usize>(), 3);
+    assert_eq!(status["Matching"], 1);
+    assert_eq!(status["Unparseable"], 1);
+    Ok(())
+}
+
+// Geocodes business licenses, identified by license number, and a CSV address column.
+// A CSV without the named column is an error, and a malformed row is reported, not dropped.
+#[test]
+fn geocode_businesses_and_csv() -> Clean<()> {
+    let file_path = "../address/data/city_addresses_20241007.csv";
+    let addresses = address::SpatialAddresses::from(
+        &address::GrantsPassSpatialAddresses::from_csv(file_path)?[..],
+    );
+    let businesses = Businesses::from_csv("./tests/test_data/businesses.csv")?;
+    let report = Geocodes::from_records(&businesses[..], &addresses);
+    assert_eq!(report.len(), 2);
+    assert_eq!(report[0].id, "BTAX-000001-01-2024");
+    assert_eq!(report[0].outcome, MatchOutcome::Matched);
+    assert_eq!(report[1].outcome, MatchOutcome::Unparseable);
+    let path = "./tests/test_data/businesses.csv";
+    let report = Geocodes::from_csv(path, "FULLADDRESS", &addresses)?;
+    assert_eq!(report[0].id, "1");
+    assert_eq!(report[0].outcome, MatchOutcome::Matched);
+    assert!(Geocodes::from_csv(path, "ADDRESS", &addresses).is_err());
+    let path = std::env::temp_dir().join("geocode_malformed.csv");
+    std::fs::write(
+        &path,
+        "Id,Address\n1,101 NW A ST\n2,101 NW A ST,Extra\n3,\n",
+    )?;
+    let report = Geocodes::from_csv(&path, "Address", &addresses)?;
+    assert_eq!(report.len(), 3);
+    assert_eq!(report[0].outcome, MatchOutcome::Matched);
+    assert_eq!(report[1].id, "2");
+    assert_eq!(report[1].outcome, MatchOutcome::Malformed);
+    assert!(report[1].error.is_some());
+    assert_eq!(report[2].outcome, MatchOutcome::Unparseable);
+    assert_eq!(report.counts()[&MatchOutcome::Malformed], 1);
+    Ok(())
+}
+
 #[test]
 fn convert_raw_bea() -> Clean<()> {
     // After running "download" from main in the `bea` crate, the download is in BeaDataRaw format.
@@ -490,3 +565,1195 @@ fn write_wastewater_events_with_reports() -> aid::prelude::Clean<()> {
 
     Ok(())
 }
+
+// Checks the mailing list filters against taxlots built in memory.
+// The owner-occupied test should ignore differences in case and spacing between the mailing
+// address and the situs address.
+#[test]
+fn taxlot_filters() {
+    let home = CountyTaxlot::default()
+        .with_address("123 NW  Main St".to_string())
+        .with_situs("123 NW MAIN ST".to_string())
+        .with_mailing_state("OR".to_string())
+        .with_property_class(101);
+    let rental = CountyTaxlot::default()
+        .with_address("PO BOX 55".to_string())
+        .with_situs("456 SE G ST".to_string())
+        .with_mailing_state("CA".to_string())
+        .with_property_class(201);
+    let lots = CountyTaxlots::from(vec![home, rental]);
+    let query = TaxlotQuery::new().with(TaxlotFilter::OwnerOccupied);
+    assert_eq!(lots.query(&query).len(), 1);
+    let query = TaxlotQuery::new()
+        .with(TaxlotFilter::Absentee)
+        .with(TaxlotFilter::OutOfState("OR".to_string()));
+    assert_eq!(lots.query(&query)[0].situs(), "456 SE G ST");
+    let query = TaxlotQuery::new().with(TaxlotFilter::PropertyClass(vec![101, 102]));
+    assert_eq!(lots.query(&query)[0].situs(), "123 NW MAIN ST");
+}
+
+// Builds a mailing list from three taxlots, two of which share a mailing address.
+// Each owner at a mailing address should appear in one row for each parcel sent to the address.
+#[test]
+fn mailing_list_rows() -> Clean<()> {
+    let lot = |owner: &str, address: &str, map: &str, situs: &str| {
+        CountyTaxlot::default()
+            .with_owner_name(owner.to_string())
+            .with_address(address.to_string())
+            .with_csz("GRANTS PASS OR 97526".to_string())
+            .with_map_number(map.to_string())
+            .with_situs(situs.to_string())
+    };
+    let lots = CountyTaxlots::from(vec![
+        lot("SMITH JOHN", "PO BOX 1", "1", "100 A ST"),
+        lot("SMITH JOHN", "PO BOX 1", "2", "200 B ST"),
+        lot("JONES MARY", "PO BOX 1", "3", "300 C ST"),
+        lot("LEE ANN", "PO BOX 9", "4", "400 D ST"),
+    ]);
+    let mail = MailingList::from_parcels(&lots)?;
+    assert_eq!(mail.len(), 2);
+    let rows = MailingListRows::from(&mail);
+    assert_eq!(rows.len(), 7);
+    assert_eq!(rows[0].name(), "JONES MARY");
+    assert_eq!(rows[0].parcel(), "1");
+    assert_eq!(rows[3].name(), "SMITH JOHN");
+    assert_eq!(rows[3].parcel(), "1");
+    let group = MailingListRow::owner_group("Jones  Mary", "po box 1", "Grants Pass OR 97526");
+    assert_eq!(rows.iter().filter(|v| v.owner_group() == &group).count(), 6);
+    assert_eq!(rows[6].name(), "LEE ANN");
+    assert_ne!(rows[6].owner_group(), &group);
+    let path = std::env::temp_dir().join("mailing_list_rows.json");
+    mail.to_json(&path)?;
+    let json: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&path)?).map_err(|e| Bandage::Hint(e.to_string()))?;
+    assert_eq!(json[0]["parcels"].as_array().map(|v| v.len()), Some(3));
+    assert_eq!(json[1]["name"], "LEE ANN");
+    Ok(())
+}
+
+// Filters a mailing list to residential taxlots of owners with at least two properties.
+// The owner of a house and a commercial lot keeps both properties in the count.
+#[test]
+fn mailing_list_query() -> Clean<()> {
+    let lot = |owner: &str, address: &str, class: i32| {
+        CountyTaxlot::default()
+            .with_owner_name(owner.to_string())
+            .with_address(address.to_string())
+            .with_situs(format!("{} SITUS", class))
+            .with_property_class(class)
+    };
+    let lots = CountyTaxlots::from(vec![
+        lot("SMITH JOHN", "PO BOX 1", 101),
+        lot("SMITH JOHN", "PO BOX 1", 201),
+        lot("LEE ANN", "PO BOX 9", 101),
+    ]);
+    let query = TaxlotQuery::new()
+        .with(TaxlotFilter::PropertyClass(vec![101]))
+        .with_min_properties(2);
+    let mail = MailingList::from_query(&lots, &query)?;
+    assert_eq!(mail.len(), 1);
+    assert_eq!(mail[0].properties(), 2);
+    let query = query.with_min_properties(1);
+    assert_eq!(MailingList::from_query(&lots, &query)?.len(), 2);
+    let query = query.with_min_properties(3);
+    assert!(MailingList::from_query(&lots, &query)?.is_empty());
+    Ok(())
+}
+
+// Reads the mailing state of city taxlots from the city, state and zip code line.
+// Lines without a zip code or a two-letter state, such as foreign addresses, have no state.
+#[test]
+fn city_mailing_state() {
+    let lot = |csz: &str| CityTaxlot::default().with_csz(csz.to_string());
+    assert_eq!(lot("GRANTS PASS OR 97526").mailing_state(), "OR");
+    assert_eq!(lot("GRANTS PASS,OR 97526-1234").mailing_state(), "OR");
+    assert_eq!(lot("SAN JOSE, CA 951251234").mailing_state(), "CA");
+    assert_eq!(lot("VANCOUVER BC V6B 1A1").mailing_state(), "");
+    assert_eq!(lot("LONDON UNITED KINGDOM").mailing_state(), "");
+    assert_eq!(lot("").mailing_state(), "");
+}
+
+// Reads a two-parcel shapefile without z values into county and city taxlots.
+// The fixture names the map number field "MAPNUM", which the county reader should also accept.
+#[test]
+fn taxlots_from_shp() -> Clean<()> {
+    let path = "./tests/test_data/taxlots.shp";
+    let county = CountyTaxlots::from_shp(path)?;
+    assert_eq!(county.len(), 2);
+    assert_eq!(county[0].account().trim(), "R100");
+    assert_eq!(county[0].map_number().trim(), "36-05-17-AB-01200");
+    assert_eq!(county[1].retail_market_value(), &Some(250000.));
+    assert_eq!(county[0].geometry().as_ref().map(|v| v.0.len()), Some(1));
+    let city = CityTaxlots::from_shp(path)?;
+    assert_eq!(city.len(), 2);
+    assert_eq!(city[1].owner_name().trim(), "JONES MARY");
+    assert_eq!(city[1].map_number().trim(), "36-05-17-AB-01300");
+    Ok(())
+}
+
+// Parses map taxlot identifiers written in the styles found in the city and county layers.
+// Each variant should parse to the same value and format back to the canonical styles.
+#[test]
+fn parse_map_taxlot() -> Clean<()> {
+    let canonical = "36-05-17-AB-01200".parse::<MapTaxlot>()?;
+    for variant in [
+        "360517AB01200",
+        "36 05 17 AB 01200",
+        "36S05W17AB01200",
+        "36-5-17-ab-1200",
+    ] {
+        assert_eq!(variant.parse::<MapTaxlot>()?, canonical);
+    }
+    assert_eq!(canonical.format(MapTaxlotStyle::Compact), "360517AB01200");
+    assert_eq!(
+        canonical.format(MapTaxlotStyle::Spaced),
+        "36 05 17 AB 01200"
+    );
+    assert_eq!(canonical.map().to_string(), "36-05-17-AB");
+    assert!(canonical.agrees(36, 5, 17));
+    let next = "36-05-17-AC-00100".parse::<MapTaxlot>()?;
+    assert!(canonical < next);
+    let east = "36S05E17AB01200".parse::<MapTaxlot>()?;
+    assert_ne!(east, canonical);
+    assert_eq!(*east.range_direction(), 'E');
+    assert_eq!(east.to_string(), "36-05E-17-AB-01200");
+    assert_eq!(
+        east.format(MapTaxlotStyle::Compact).parse::<MapTaxlot>()?,
+        east
+    );
+    Ok(())
+}
+
+// Summarizes taxlot values by zone.  The second residential lot has no assessor acreage, so its
+// GIS acreage is used, and no improvements, so it is left out of the taxes per square foot.
+#[test]
+fn valuation_summary() {
+    let lot = |zone: &str, market: f64, assessed: f64, taxes: f64| {
+        CountyTaxlot::default()
+            .with_zone(zone.to_string())
+            .with_retail_market_value(Some(market))
+            .with_assessed_value(Some(assessed))
+            .with_taxes(Some(taxes))
+    };
+    let lots = CountyTaxlots::from(vec![
+        lot("R1", 200000., 100000., 2000.)
+            .with_acreage(Some(0.5))
+            .with_square_feet(Some(1000.)),
+        lot("R1", 100000., 80000., 1000.).with_gis_acres(0.25),
+        lot("C", 300000., 300000., 3000.)
+            .with_acreage(Some(1.))
+            .with_square_feet(Some(2000.)),
+    ]);
+    let summary = ValuationSummaries::from_parcels(&lots, TaxlotGroup::Zone);
+    assert_eq!(summary.len(), 2);
+    assert_eq!(summary[0].group(), "C");
+    assert_eq!(*summary[0].ratio_median(), Some(1.));
+    assert_eq!(*summary[0].taxes_per_square_foot_median(), Some(1.5));
+    let residential = &summary[1];
+    assert_eq!(*residential.count(), 2);
+    assert!((residential.ratio_median().unwrap_or_default() - 0.65).abs() < 1e-9);
+    assert_eq!(*residential.taxes_total(), 3000.);
+    assert_eq!(*residential.taxes_per_acre_median(), Some(4000.));
+    assert_eq!(*residential.taxes_per_square_foot_median(), Some(2.));
+}
+
+// Checks quartiles against values computed by hand, using linear interpolation between ranks.
+#[test]
+fn distribution_quartiles() {
+    let values = [4., 1., 3., 2., f64::NAN, 5.];
+    let dist = spreadsheet::stats::Distribution::new(&values).unwrap();
+    assert_eq!(*dist.count(), 5);
+    assert_eq!(*dist.median(), 3.);
+    assert_eq!(*dist.q1(), 2.);
+    assert_eq!(*dist.q3(), 4.);
+    assert_eq!(*dist.mean(), 3.);
+    assert!(spreadsheet::stats::Distribution::new(&[]).is_none());
+}
+
+// Runs the sales ratio study on three sales in one neighborhood.
+// Ratios are 0.8, 1.0 and 1.2, so the median is 1.0 and the COD is 100 * (0.4 / 3) / 1.0.
+#[test]
+fn sales_ratio_study() {
+    let lots = [(100000., 80000.), (200000., 200000.), (150000., 180000.)]
+        .iter()
+        .map(|(price, market)| {
+            CountyTaxlot::default()
+                .with_neighborhood("101".to_string())
+                .with_sale_date(Some("6/15/2023".to_string()))
+                .with_sale_price(Some(*price))
+                .with_sale_type(Some("1".to_string()))
+                .with_retail_market_value(Some(*market))
+        })
+        .collect::<Vec<CountyTaxlot>>();
+    let sales = Sales::from_parcels(&CountyTaxlots::from(lots));
+    assert_eq!(sales.arms_length(&["1".to_string()]).len(), 3);
+    assert!(sales.arms_length(&["2".to_string()]).is_empty());
+    let study = RatioStudy::from(&sales);
+    assert_eq!(study.len(), 1);
+    assert_eq!(study[0].year, 2023);
+    assert!((study[0].median_ratio - 1.).abs() < 1e-9);
+    assert!((study[0].cod.unwrap_or_default() - 40. / 3.).abs() < 1e-9);
+    let unvalued = CountyTaxlot::default()
+        .with_neighborhood("102".to_string())
+        .with_sale_date(Some("6/15/2023".to_string()))
+        .with_sale_price(Some(100000.))
+        .with_retail_market_value(Some(0.));
+    let study = RatioStudy::from(&Sales::from_parcels(&CountyTaxlots::from(vec![unvalued])));
+    assert_eq!(study[0].median_ratio, 0.);
+    assert_eq!(study[0].cod, None);
+    assert_eq!(study[0].prd, None);
+}
+
+// Compares two snapshots of three taxlots: one sold, one retired and one new.
+// Owner names that differ only in case and spacing are not an ownership transfer.
+#[test]
+fn taxlot_snapshot_diff() {
+    let lot = |account: &str, owner: &str, value: f64| {
+        CountyTaxlot::default()
+            .with_account(account.to_string())
+            .with_owner_name(owner.to_string())
+            .with_retail_market_value(Some(value))
+    };
+    let previous = CountyTaxlots::from(vec![lot("1", "SMITH", 100.), lot("2", "JONES", 100.)]);
+    let current = CountyTaxlots::from(vec![lot("1", "LEE", 105.), lot("3", "JONES", 50.)]);
+    let changes = TaxlotChanges::compare(&previous, &current, 0.1);
+    let kinds = changes
+        .iter()
+        .map(|v| v.kind)
+        .collect::<Vec<TaxlotChangeKind>>();
+    assert_eq!(
+        kinds,
+        vec![
+            TaxlotChangeKind::OwnershipTransfer,
+            TaxlotChangeKind::RetiredAccount,
+            TaxlotChangeKind::NewAccount
+        ]
+    );
+    assert_eq!(changes[0].current, Some("LEE".to_string()));
+    let current = CountyTaxlots::from(vec![lot("1", "smith", 100.), lot("2", " Jones ", 100.)]);
+    assert!(TaxlotChanges::compare(&previous, &current, 0.1).is_empty());
+}
+
+// Reports an account shared by two taxlots in the current snapshot as a duplicate.
+// The mailing address differs only in case and spacing, so it is not reported as a change.
+#[test]
+fn taxlot_snapshot_duplicates() {
+    let lot = |account: &str, address: &str| {
+        CountyTaxlot::default()
+            .with_account(account.to_string())
+            .with_address(address.to_string())
+            .with_csz("GRANTS PASS OR 97526".to_string())
+    };
+    let previous = CountyTaxlots::from(vec![lot("1", "PO BOX 1")]);
+    let current = CountyTaxlots::from(vec![lot("1", "po box  1"), lot("1", "PO BOX 2")]);
+    let changes = TaxlotChanges::compare(&previous, &current, 0.1);
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].kind, TaxlotChangeKind::DuplicateAccount);
+    assert_eq!(changes[0].current, Some("2".to_string()));
+    assert_eq!(changes[0].previous, None);
+}
+
+// Reconciles a city taxlot against county taxlots, joining on map number when the account is
+// blank and allowing a tolerance on market value.
+#[test]
+fn reconcile_city_county() {
+    let city = CityTaxlots::from(vec![CityTaxlot::default()
+        .with_map_number("360517AB01200".to_string())
+        .with_owner_name("Smith  John".to_string())
+        .with_retail_market_value(Some(100.))]);
+    let county = CountyTaxlots::from(vec![
+        CountyTaxlot::default()
+            .with_map_number("36-05-17-AB-01200".to_string())
+            .with_owner_name("SMITH JOHN".to_string())
+            .with_retail_market_value(Some(101.)),
+        CountyTaxlot::default().with_account("2".to_string()),
+    ]);
+    let options =
+        ReconcileOptions::new(vec![ReconcileField::OwnerName, ReconcileField::MarketValue]);
+    let report = Reconciliation::compare(&city, &county, &options);
+    assert_eq!(report.len(), 2);
+    assert_eq!(report[0].field, Some(ReconcileField::MarketValue));
+    assert_eq!(report[1].status, ReconcileStatus::CountyOnly);
+    let options = options.with_tolerance(ReconcileField::MarketValue, 5.);
+    let report = Reconciliation::compare(&city, &county, &options);
+    assert_eq!(report.len(), 1);
+}
+
+// Taxlots with a blank account and a blank map number cannot be joined, so they are reported as
+// present on one side only rather than joined to each other.  Repeated accounts are reported.
+#[test]
+fn reconcile_blank_map_numbers() -> Clean<()> {
+    let city = CityTaxlots::from(vec![CityTaxlot::default()]);
+    let county = CountyTaxlots::from(vec![CountyTaxlot::default()]);
+    let options = ReconcileOptions::new(vec!["owner_name".parse::<ReconcileField>()?]);
+    let report = Reconciliation::compare(&city, &county, &options);
+    let status = report
+        .iter()
+        .map(|v| v.status)
+        .collect::<Vec<ReconcileStatus>>();
+    assert_eq!(
+        status,
+        vec![ReconcileStatus::CityOnly, ReconcileStatus::CountyOnly]
+    );
+    assert!("acreage".parse::<ReconcileField>().is_err());
+    // An account on two taxlots in either layer is a duplicate, after joining the first of them.
+    let city = CityTaxlots::from(vec![
+        CityTaxlot::default().with_account("1".to_string()),
+        CityTaxlot::default().with_account("1".to_string()),
+    ]);
+    let county = CountyTaxlots::from(vec![
+        CountyTaxlot::default().with_account("1".to_string()),
+        CountyTaxlot::default().with_account(" 1".to_string()),
+    ]);
+    let report = Reconciliation::compare(&city, &county, &options);
+    let status = report
+        .iter()
+        .map(|v| v.status)
+        .collect::<Vec<ReconcileStatus>>();
+    assert_eq!(
+        status,
+        vec![
+            ReconcileStatus::CityDuplicate,
+            ReconcileStatus::CountyDuplicate,
+            ReconcileStatus::CityOnly,
+            ReconcileStatus::CountyOnly
+        ]
+    );
+    assert_eq!(report.counts()["CountyDuplicate"], 1);
+    Ok(())
+}
+
+// Survey answers match the allowed answers ignoring case, and other answers are rejected.
+#[test]
+fn survey_answers() {
+    let config = SurveyConfig::jc_survey();
+    let question = &config.questions[0];
+    assert_eq!(
+        question
+            .answer(" option 6: reduce police/fire staffing ")
+            .unwrap(),
+        "Option 6: Reduce Police/Fire Staffing"
+    );
+    assert!(question.answer("Option 7").is_err());
+    assert_eq!(
+        SurveyQuestion::new("Comment", &[])
+            .answer(" Anything ")
+            .unwrap(),
+        "Anything"
+    );
+}
+
+// Duplicate survey responses from one address are dropped or down-weighted, and addresses with
+// too many responses are flagged.
+#[test]
+fn survey_duplicates() {
+    let item = |row: usize, address: &str| SurveyExportItem {
+        row,
+        address: address.to_string(),
+        answers: Default::default(),
+        weight: 1.,
+        x_coordinate: 0.,
+        y_coordinate: 0.,
+    };
+    let export = SurveyExport {
+        questions: Vec::new(),
+        records: vec![item(1, "1 A ST"), item(2, "2 B ST"), item(3, "1 A ST")],
+    };
+    let first = export.deduplicate(DuplicatePolicy::KeepFirst);
+    assert_eq!(
+        first.records.iter().map(|v| v.row).collect::<Vec<usize>>(),
+        vec![1, 2]
+    );
+    let last = export.deduplicate(DuplicatePolicy::KeepLast);
+    assert_eq!(
+        last.records.iter().map(|v| v.row).collect::<Vec<usize>>(),
+        vec![2, 3]
+    );
+    let weighted = export.deduplicate(DuplicatePolicy::Weighted);
+    assert_eq!(weighted.records[0].weight, 0.5);
+    assert_eq!(weighted.records[1].weight, 1.);
+    let non_residential = ["2 B ST".to_string()].into_iter().collect();
+    let audit = SurveyAudit::new(&export, 1, &non_residential);
+    assert_eq!(audit.len(), 2);
+    assert_eq!(audit[0].responses, 2);
+    assert!(audit[1].non_residential);
+}
+
+// Duplicate responses are kept unless a policy is chosen, and only taxlots outside the
+// residential, tract, farm, forest and multi-family classes flag their situs as non-residential.
+#[test]
+fn survey_non_residential() -> Clean<()> {
+    assert_eq!(DuplicatePolicy::default(), DuplicatePolicy::KeepAll);
+    let file_path = "../address/data/city_addresses_20241007.csv";
+    let addresses = address::SpatialAddresses::from(
+        &address::GrantsPassSpatialAddresses::from_csv(file_path)?[..],
+    );
+    let lot = |class: i32, situs: &str| {
+        CountyTaxlot::default()
+            .with_property_class(class)
+            .with_situs(situs.to_string())
+    };
+    let residential = CountyTaxlots::from(vec![lot(101, "101 NW A ST"), lot(401, "101 NW A ST")]);
+    assert!(SurveyAudit::non_residential(&residential, &addresses).is_empty());
+    let commercial = CountyTaxlots::from(vec![lot(201, "101 NW A ST"), lot(0, "101 NW A ST")]);
+    let flagged = SurveyAudit::non_residential(&commercial, &addresses);
+    let report = Geocodes::from_records(commercial.records(), &addresses);
+    assert_eq!(flagged.len(), 1);
+    assert!(flagged.contains(report[0].address_label.as_ref().unwrap()));
+    Ok(())
+}
+
+// Survey responses are assigned to the polygon containing them, and tallied by weight.
+#[test]
+fn survey_cross_tab() {
+    let square = |x: f64| {
+        geo::MultiPolygon::new(vec![geo::Polygon::new(
+            geo::LineString::from(vec![(x, 0.), (x + 1., 0.), (x + 1., 1.), (x, 1.), (x, 0.)]),
+            vec![],
+        )])
+    };
+    let areas = Areas::from(vec![
+        Area {
+            name: "West".to_string(),
+            geometry: square(0.),
+        },
+        Area {
+            name: "East".to_string(),
+            geometry: square(1.),
+        },
+    ]);
+    let item = |x: f64, answer: &str| SurveyExportItem {
+        row: 0,
+        address: String::new(),
+        answers: [("Vote".to_string(), answer.to_string())]
+            .into_iter()
+            .collect(),
+        weight: 1.,
+        x_coordinate: x,
+        y_coordinate: 0.5,
+    };
+    let export = SurveyExport {
+        questions: vec!["Vote".to_string()],
+        records: vec![
+            item(0.5, "Yes"),
+            item(0.25, "No"),
+            item(1.5, "Yes"),
+            item(5., "Yes"),
+        ],
+    };
+    let tab = CrossTab::new(&export, "Vote", &areas);
+    assert_eq!(tab.answers, vec!["No".to_string(), "Yes".to_string()]);
+    assert_eq!(tab.rows.len(), 2);
+    assert_eq!(tab.rows[0].area, "East");
+    assert_eq!(tab.rows[1].counts, vec![1., 1.]);
+    assert_eq!(tab.rows[1].share(1), 0.5);
+    assert_eq!(tab.rows[1].effective_size(), 2.);
+    assert!(tab.rows[1].margin_of_error(1) > 0.);
+    // Two duplicate responses weighted by one half count for less than two unit responses.
+    let mut weighted = export.clone();
+    weighted.records[1].weight = 0.5;
+    weighted.records.push(item(0.75, "No"));
+    weighted.records[4].weight = 0.5;
+    let tab = CrossTab::new(&weighted, "Vote", &areas);
+    assert_eq!(tab.rows[1].total, 2.);
+    assert!((tab.rows[1].effective_size() - 8. / 3.).abs() < 1e-9);
+    let moe = 1.96 * (0.25_f64 / (8. / 3.)).sqrt();
+    assert!((tab.rows[1].margin_of_error(1) - moe).abs() < 1e-9);
+}
+
+// Areas with a repeated or missing name would be merged in a cross tab, so reading them fails.
+#[test]
+fn survey_areas_unique() -> Clean<()> {
+    let feature = |properties: serde_json::Value, x: f64| {
+        serde_json::json!({
+            "type": "Feature",
+            "properties": properties,
+            "geometry": {
+                "type": "Polygon",
+                "coordinates": [[[x, 0.], [x + 1., 0.], [x + 1., 1.], [x, 1.], [x, 0.]]],
+            },
+        })
+    };
+    let path = std::env::temp_dir().join("survey_areas_unique.geojson");
+    let write = |features: Vec<serde_json::Value>| {
+        let collection = serde_json::json!({"type": "FeatureCollection", "features": features});
+        std::fs::write(&path, collection.to_string())
+    };
+    let west = feature(serde_json::json!({"ward": "West"}), 0.);
+    let east = feature(serde_json::json!({"ward": "East"}), 1.);
+    write(vec![west.clone(), east])?;
+    assert_eq!(Areas::from_geojson(&path, "ward")?.len(), 2);
+    write(vec![west.clone(), west.clone()])?;
+    assert!(Areas::from_geojson(&path, "ward").is_err());
+    write(vec![west, feature(serde_json::json!({}), 1.)])?;
+    assert!(Areas::from_geojson(&path, "ward").is_err());
+    Ok(())
+}
+
+// Survey responses with an invalid answer or a malformed row are kept with their outcome rather
+// than aborting the import.
+#[test]
+fn survey_rejected_responses() -> Clean<()> {
+    let path = std::env::temp_dir().join("survey_rejected_responses.csv");
+    std::fs::write(
+        &path,
+        "Address,Vote\n\"1 A ST\",Yes\n\"1 A ST\",Maybe\n\"2 B ST\",Yes,Extra\n",
+    )?;
+    let config =
+        SurveyConfig::new("Address").with_question(SurveyQuestion::new("Vote", &["Yes", "No"]));
+    let survey = Survey::from_csv(&path, &config)?;
+    assert_eq!(survey.records.len() + survey.rejected.len(), 3);
+    assert!(survey
+        .rejected
+        .iter()
+        .any(|v| v.row == 2 && v.outcome == SurveyOutcome::InvalidAnswer));
+    assert!(survey
+        .rejected
+        .iter()
+        .any(|v| v.row == 3 && v.outcome == SurveyOutcome::MalformedRow));
+    Ok(())
+}
+
+// Every row of the survey file is reported with its own outcome, in row order.
+#[test]
+fn survey_outcomes() -> Clean<()> {
+    let file_path = "../address/data/city_addresses_20241007.csv";
+    let addresses = address::SpatialAddresses::from(
+        &address::GrantsPassSpatialAddresses::from_csv(file_path)?[..],
+    );
+    let path = std::env::temp_dir().join("survey_outcomes.csv");
+    std::fs::write(
+        &path,
+        "Address,Vote\n\
+         101 NW A ST,Yes\n\
+         99999 NW NOWHERE AVE,No\n\
+         101 NW A ST,Maybe\n\
+         ,Yes\n\
+         101 NW A ST,Yes,Extra\n",
+    )?;
+    let config =
+        SurveyConfig::new("Address").with_question(SurveyQuestion::new("Vote", &["Yes", "No"]));
+    let outcomes = Survey::from_csv(&path, &config)?.outcomes(&addresses);
+    assert_eq!(
+        outcomes
+            .iter()
+            .map(|v| v.outcome)
+            .collect::<Vec<SurveyOutcome>>(),
+        vec![
+            SurveyOutcome::ExactMatch,
+            SurveyOutcome::NoMatch,
+            SurveyOutcome::InvalidAnswer,
+            SurveyOutcome::ParseFailure,
+            SurveyOutcome::MalformedRow,
+        ]
+    );
+    assert_eq!(
+        outcomes.iter().map(|v| v.row).collect::<Vec<usize>>(),
+        vec![1, 2, 3, 4, 5]
+    );
+    assert!(outcomes[0].address.is_some());
+    assert!(outcomes[0].x_coordinate.is_some());
+    assert!(outcomes[1].address.is_none());
+    assert_eq!(outcomes.counts()[&SurveyOutcome::ExactMatch], 1);
+    Ok(())
+}
+
+// The 2023 revenue survey reads through the preset configuration, and only responses matched to
+// an address with coordinates are exported.
+#[test]
+fn jc_survey_preset() -> Clean<()> {
+    let file_path = "../address/data/city_addresses_20241007.csv";
+    let addresses = address::SpatialAddresses::from(
+        &address::GrantsPassSpatialAddresses::from_csv(file_path)?[..],
+    );
+    let path = std::env::temp_dir().join("jc_survey_preset.csv");
+    std::fs::write(
+        &path,
+        "City,Address,Option\n\
+         Grants Pass,101 NW A ST,Option 4: Utility Fee/Sales Tax\n\
+         Grants Pass,99999 NW NOWHERE AVE,Option 4: Utility Fee/Sales Tax\n",
+    )?;
+    let survey = Survey::from_csv(&path, &SurveyConfig::jc_survey())?;
+    assert_eq!(survey.records.len(), 2);
+    assert_eq!(survey.records[0].city, Some("Grants Pass".to_string()));
+    let export = survey.validate(&addresses);
+    assert_eq!(export.questions, vec!["Option".to_string()]);
+    assert_eq!(export.records.len(), 1);
+    assert_eq!(export.records[0].row, 1);
+    assert_ne!(export.records[0].x_coordinate, 0.);
+    assert_ne!(export.records[0].y_coordinate, 0.);
+    // The deprecated reader is a thin wrapper over the preset.
+    #[allow(deprecated)]
+    let legacy = JcSurvey::from_csv(&path)?;
+    assert_eq!(legacy.records.len(), survey.records.len());
+    assert_eq!(legacy.validate(&addresses).records.len(), 1);
+    Ok(())
+}
+
+// Builds a BEA record for the tests below, described by the line-code catalog of its table.
+fn bea_datum(code: &str, fips: i32, year: i32, unit: &str, mult: i32, value: BeaValue) -> BeaDatum {
+    let description = BeaTable::parse_code(code)
+        .ok()
+        .and_then(|(table, line)| table.line_codes().get(&line).cloned())
+        .unwrap_or_else(|| code.to_string());
+    BeaDatum {
+        code: code.to_string(),
+        geo_fips: fips,
+        geo_name: String::new(),
+        time_period: year,
+        description,
+        cl_unit: unit.to_string(),
+        unit_mult: mult,
+        data_value: value,
+    }
+}
+
+// BEA line codes are read into the table and line number, so records from several tables can
+// share one dataset.
+#[test]
+fn bea_tables() -> Clean<()> {
+    assert_eq!(BeaTable::parse_code("CAINC5N-10")?, (BeaTable::Cainc5n, 10));
+    assert_eq!(
+        BeaTable::parse_code("CAEMP25N-70")?,
+        (BeaTable::Caemp25n, 70)
+    );
+    assert_eq!(
+        BeaTable::parse_code("SAINC1-3")?,
+        (BeaTable::Other("SAINC1".to_string()), 3)
+    );
+    assert!(BeaTable::parse_code("CAINC1").is_err());
+    let datum = |code: &str| BeaDatum {
+        description: code.to_string(),
+        ..bea_datum(
+            code,
+            41033,
+            2022,
+            "Thousands of dollars",
+            3,
+            BeaValue::Number(1),
+        )
+    };
+    let data = BeaData::from(vec![
+        datum("CAINC1-1"),
+        datum("CAINC5N-10"),
+        datum("CAINC1-3"),
+    ]);
+    assert_eq!(data.tables(), vec![BeaTable::Cainc1, BeaTable::Cainc5n]);
+    assert_eq!(data.table(&BeaTable::Cainc1).len(), 2);
+    assert_eq!(
+        data.line_codes(&BeaTable::Cainc1)
+            .keys()
+            .collect::<Vec<&u32>>(),
+        vec![&1, &3]
+    );
+    // Cataloged lines take the published description rather than the record description.
+    assert_eq!(
+        data.line_codes(&BeaTable::Cainc1)[&3],
+        "Per capita personal income"
+    );
+    let catalog = BeaTable::Caemp25n.line_codes();
+    assert_eq!(catalog[&10], "Total employment");
+    assert_eq!(catalog[&1800], "Accommodation and food services");
+    assert_eq!(
+        BeaTable::Cainc5n.line_codes()[&46],
+        "Plus: Dividends, interest, and rent"
+    );
+    assert_eq!(
+        BeaTable::Cainc5n.line_codes()[&511],
+        "Wood product manufacturing"
+    );
+    assert!(!BeaTable::Caemp25n.line_codes().contains_key(&511));
+    assert!(BeaTable::Other("SAINC1".to_string())
+        .line_codes()
+        .is_empty());
+    assert_eq!(BeaTable::Cainc1.csv_var(), "BEA_CAINC1_CSV");
+    // Tables without a built-in catalog read the lines from a BEA line-code file.
+    let path = std::env::temp_dir().join("bea_cainc30_lines.csv");
+    std::fs::write(
+        &path,
+        "LineCode,Description
+10,Personal income (thousands of dollars)
+",
+    )?;
+    assert_eq!(BeaTable::read_lines(&path)?.len(), 1);
+    std::env::set_var(BeaTable::Cainc30.lines_var(), &path);
+    assert_eq!(
+        BeaTable::Cainc30.catalog()[&10],
+        "Personal income (thousands of dollars)"
+    );
+    Ok(())
+}
+
+// BEA records pivot into series by location and line code, with growth rates and an index.
+#[test]
+fn bea_pivot() {
+    let datum = |year: i32, value: i64| {
+        bea_datum(
+            "CAINC1-1",
+            41033,
+            year,
+            "Thousands of dollars",
+            3,
+            BeaValue::Number(value),
+        )
+    };
+    let data = BeaData::from(vec![datum(2020, 100), datum(2021, 110), datum(2022, 121)]);
+    let pivot = data.pivot();
+    assert_eq!(pivot.len(), 1);
+    assert_eq!(pivot.years(), vec![2020, 2021, 2022]);
+    let series = &pivot[0];
+    assert_eq!(series.measure, BeaMeasure::Dollars);
+    assert_eq!(series.change(2021), Some(10000.));
+    assert_eq!(series.percent_change(2022), Some(10.));
+    assert_eq!(series.percent_change(2020), None);
+    assert!((series.cagr(2020, 2022).unwrap() - 10.).abs() < 1e-9);
+    assert_eq!(series.index(2020).value(2022), Some(121.));
+    assert_eq!(pivot.percent_changes()[0].values.len(), 2);
+}
+
+// Dollar series convert to constant dollars of the base year, and other units are refused.
+#[test]
+fn bea_deflator() -> Clean<()> {
+    let deflator = Deflator {
+        name: "PCE".to_string(),
+        values: [(2020, 100.), (2022, 110.)].into_iter().collect(),
+    };
+    let series = BeaSeries {
+        code: "CAINC1-1".to_string(),
+        unit: "Dollars".to_string(),
+        cl_unit: "Thousands of dollars".to_string(),
+        values: [(2020, 1000.), (2022, 1100.)].into_iter().collect(),
+        ..Default::default()
+    };
+    let real = deflator.deflate(&series, 2020)?;
+    assert_eq!(real.value(2022), Some(1000.));
+    assert_eq!(real.unit, "Dollars (constant 2020 dollars, PCE)");
+    assert!(deflator.deflate(&real, 2020).is_err());
+    assert!(deflator.deflate(&series, 2021).is_err());
+    // Series already in real dollars are not deflated twice.
+    assert!(Deflator::is_nominal("Thousands of dollars"));
+    assert!(!Deflator::is_nominal("Millions of chained 2017 dollars"));
+    assert!(!Deflator::is_nominal("Real dollars"));
+    let chained = BeaSeries {
+        cl_unit: "Millions of chained 2017 dollars".to_string(),
+        ..series.clone()
+    };
+    assert!(deflator.deflate(&chained, 2020).is_err());
+    // The pivot keeps the unit reported by BEA, so chained dollars pass through unchanged.
+    let data = BeaData::from(vec![
+        bea_datum(
+            "CAGDP9-1",
+            41033,
+            2020,
+            "Thousands of chained 2017 dollars",
+            3,
+            BeaValue::Number(1000),
+        ),
+        bea_datum(
+            "CAGDP9-1",
+            41033,
+            2022,
+            "Thousands of chained 2017 dollars",
+            3,
+            BeaValue::Number(1100),
+        ),
+    ]);
+    let pivot = data.pivot();
+    let deflated = pivot.deflate(&deflator, 2020)?;
+    assert_eq!(deflated, pivot);
+    assert_eq!(deflated[0].value(2022), Some(1100000.));
+    Ok(())
+}
+
+// BEA values apply the unit multiplier, and series arithmetic refuses to mix measures.
+#[test]
+fn bea_measures() -> Clean<()> {
+    let datum = |code: &str, unit: &str, mult: i32, value: i64| {
+        bea_datum(code, 41033, 2022, unit, mult, BeaValue::Number(value))
+    };
+    let income = datum("CAINC1-1", "Thousands of dollars", 3, 50);
+    let population = datum("CAINC1-2", "Number of persons", 0, 1000);
+    let per_capita = datum("CAINC1-3", "Dollars", 0, 50);
+    assert_eq!(income.value(), Some(50000.));
+    assert_eq!(income.measure(), BeaMeasure::Dollars);
+    assert_eq!(population.measure(), BeaMeasure::Persons);
+    assert_eq!(per_capita.measure(), BeaMeasure::DollarsPerCapita);
+    assert_eq!(
+        BeaMeasure::new("Thousands of dollars per household", ""),
+        BeaMeasure::Other("Thousands of dollars per household".to_string())
+    );
+    let chained = datum("CAGDP9-1", "Millions of chained 2017 dollars", 6, 5);
+    assert_eq!(
+        chained.measure(),
+        BeaMeasure::RealDollars("Chained 2017 dollars".to_string())
+    );
+    assert_eq!(
+        BeaMeasure::new(&chained.measure().to_string(), ""),
+        chained.measure()
+    );
+    assert_eq!(
+        BeaMeasure::new("Dollars per job", ""),
+        BeaMeasure::Other("Dollars per job".to_string())
+    );
+    let data = BeaData::from(vec![income, population, per_capita, chained]);
+    let path = std::env::temp_dir().join("bea_measures.csv");
+    data.to_normalized_csv(&path)?;
+    let normalized = utils::from_csv::<BeaNormalized, _>(&path)?;
+    assert_eq!(normalized[0].unit, BeaMeasure::Dollars.to_string());
+    assert_eq!(normalized[0].value, Some(50000.));
+    assert_eq!(normalized[1].unit, BeaMeasure::Persons.to_string());
+    assert_eq!(normalized[3].unit, "Chained 2017 dollars");
+    let pivot = data.pivot();
+    let income = pivot.iter().find(|v| v.code == "CAINC1-1").unwrap();
+    let population = pivot.iter().find(|v| v.code == "CAINC1-2").unwrap();
+    let per_capita = pivot.iter().find(|v| v.code == "CAINC1-3").unwrap();
+    assert_eq!(income.add(income)?.value(2022), Some(100000.));
+    assert!(income.add(population).is_err());
+    let derived = income.per_capita(population)?;
+    assert_eq!(derived.measure, BeaMeasure::DollarsPerCapita);
+    assert_eq!(derived.value(2022), Some(50.));
+    assert_eq!(derived.subtract(per_capita)?.value(2022), Some(0.));
+    assert!(population.per_capita(income).is_err());
+    Ok(())
+}
+
+// BEA values keep suppression codes and estimate flags instead of dropping the record.
+#[test]
+fn bea_values() -> Clean<()> {
+    assert_eq!("1,234,567".parse::<BeaValue>()?, BeaValue::Number(1234567));
+    assert_eq!("-56".parse::<BeaValue>()?, BeaValue::Number(-56));
+    assert_eq!("1,234 E".parse::<BeaValue>()?, BeaValue::Estimate(1234));
+    assert_eq!("(D)".parse::<BeaValue>()?, BeaValue::Suppressed);
+    assert_eq!("(NA)".parse::<BeaValue>()?, BeaValue::NotAvailable);
+    assert_eq!("(NM)".parse::<BeaValue>()?, BeaValue::NotMeaningful);
+    assert_eq!("(L)".parse::<BeaValue>()?, BeaValue::Less);
+    assert!("abc".parse::<BeaValue>().is_err());
+    assert!(BeaValue::Estimate(1234).is_estimate());
+    assert_eq!(BeaValue::Suppressed.number(), None);
+    assert_eq!(BeaValue::Number(0).number(), Some(0));
+
+    let datum = |year: i32, value: BeaValue| {
+        bea_datum("CAINC5N-500", 41033, year, "Thousands of dollars", 3, value)
+    };
+    let data = BeaData::from(vec![
+        datum(2021, BeaValue::Number(0)),
+        datum(2022, BeaValue::Suppressed),
+    ]);
+    assert_eq!(data.missing().get(BeaValue::Suppressed.reason()), Some(&1));
+    let pivot = data.pivot();
+    assert_eq!(pivot.years(), vec![2021, 2022]);
+    assert_eq!(pivot[0].value(2021), Some(0.));
+    assert_eq!(pivot[0].value(2022), None);
+    assert_eq!(pivot[0].note(2022), Some(&BeaValue::Suppressed));
+    Ok(())
+}
+
+// Location quotients and shift-share components compare a county to a benchmark area.
+#[test]
+fn bea_shift_share() -> Clean<()> {
+    let datum = |fips: i32, line: &str, year: i32, value: i64| {
+        bea_datum(
+            &format!("CAINC5N-{}", line),
+            fips,
+            year,
+            "Thousands of dollars",
+            3,
+            BeaValue::Number(value),
+        )
+    };
+    let data = BeaData::from(vec![
+        datum(41033, "35", 2020, 100),
+        datum(41033, "35", 2022, 120),
+        datum(41033, "100", 2020, 40),
+        datum(41033, "100", 2022, 60),
+        datum(41033, "46", 2020, 30),
+        datum(41033, "50", 2020, 70),
+        datum(41033, "90", 2020, 95),
+        datum(US_FIPS, "35", 2020, 1000),
+        datum(US_FIPS, "35", 2022, 1100),
+        datum(US_FIPS, "100", 2020, 100),
+        datum(US_FIPS, "100", 2022, 120),
+        datum(US_FIPS, "46", 2020, 300),
+        datum(US_FIPS, "50", 2020, 700),
+        datum(US_FIPS, "90", 2020, 950),
+    ]);
+    let analysis = BeaAnalysis::new(41033, US_FIPS, 2020, 2022);
+    let quotients = analysis.location_quotients(&data)?;
+    // Dividends (46), wages (50) and private nonfarm earnings (90) are not industries.
+    assert_eq!(quotients.len(), 2);
+    assert!(quotients.iter().all(|v| v.code == "CAINC5N-100"));
+    assert!((quotients[0].quotient.unwrap() - 4.).abs() < 1e-9);
+    let shares = analysis.shift_share(&data)?;
+    assert_eq!(shares.len(), 1);
+    let share = &shares[0];
+    assert!((share.change - 20000.).abs() < 1e-6);
+    assert!((share.national_growth - 4000.).abs() < 1e-6);
+    assert!((share.industry_mix - 4000.).abs() < 1e-6);
+    assert!((share.competitive - 12000.).abs() < 1e-6);
+    assert!(BeaAnalysis::new(41033, US_FIPS, 2020, 2022)
+        .with_total("CAINC1-1")
+        .location_quotients(&data)
+        .is_err());
+    Ok(())
+}
+
+// The CAINC5N hierarchy finds children from the data and flags sums broken by suppression.
+#[test]
+fn bea_hierarchy() {
+    let datum = |fips: i32, line: u32, value: BeaValue| {
+        bea_datum(
+            &format!("CAINC5N-{}", line),
+            fips,
+            2022,
+            "Thousands of dollars",
+            3,
+            value,
+        )
+    };
+    let mut records = Vec::new();
+    for (fips, total, mining) in [
+        (1, BeaValue::Number(100), BeaValue::Number(30)),
+        (2, BeaValue::Number(100), BeaValue::Suppressed),
+        (3, BeaValue::Suppressed, BeaValue::Number(30)),
+    ] {
+        records.push(datum(fips, 200, total));
+        records.push(datum(fips, 201, BeaValue::Number(70)));
+        records.push(datum(fips, 202, mining));
+        records.push(datum(fips, 300, BeaValue::Number(5)));
+    }
+    let data = BeaData::from(records);
+    let hierarchy = BeaHierarchy::new(&data, &BeaTable::Cainc5n);
+    assert_eq!(hierarchy.children(200), vec![201, 202]);
+    assert_eq!(hierarchy.parent(202), Some(200));
+    assert_eq!(hierarchy.depth(201), 1);
+    assert!(hierarchy.children(300).is_empty());
+    assert_eq!(hierarchy.tree().len(), 4);
+    let checks = hierarchy.check(&data);
+    assert_eq!(checks.len(), 3);
+    assert_eq!(checks[0].status, BeaCheckStatus::Consistent);
+    assert_eq!(checks[1].status, BeaCheckStatus::Suppressed);
+    assert_eq!(checks[1].missing, "CAINC5N-202");
+    assert_eq!(checks[1].difference, Some(30000.));
+    assert_eq!(checks[2].status, BeaCheckStatus::MissingParent);
+    assert_eq!(hierarchy.rollup(&data, 3, 2022, 200), Some(100000.));
+    assert_eq!(hierarchy.rollup(&data, 2, 2022, 202), None);
+}
+
+// Derived series divide line codes across tables, track units and keep missing reasons.
+#[test]
+fn bea_derived() -> Clean<()> {
+    let datum = |code: &str, unit: &str, mult: i32, fips: i32, value: BeaValue| {
+        bea_datum(code, fips, 2022, unit, mult, value)
+    };
+    let data = BeaData::from(vec![
+        datum(
+            "CAINC5N-35",
+            "Thousands of dollars",
+            3,
+            1,
+            BeaValue::Number(500),
+        ),
+        datum(
+            "CAINC5N-47",
+            "Thousands of dollars",
+            3,
+            1,
+            BeaValue::Number(125),
+        ),
+        datum("CAEMP25N-10", "Number of jobs", 0, 1, BeaValue::Number(8)),
+        datum(
+            "CAINC5N-35",
+            "Thousands of dollars",
+            3,
+            2,
+            BeaValue::Suppressed,
+        ),
+        datum("CAEMP25N-10", "Number of jobs", 0, 2, BeaValue::Number(0)),
+    ]);
+    let expression = "(CAINC5N-35 - CAINC5N-47) / 2".parse::<BeaExpression>()?;
+    assert_eq!(expression.codes().len(), 2);
+    assert!("CAINC5N-35 +".parse::<BeaExpression>().is_err());
+    let derived = vec![
+        "DERIVED-1 = CAINC5N-35 / CAEMP25N-10".parse::<BeaDerived>()?,
+        BeaDerived::new(
+            "DERIVED-2",
+            "Transfer share",
+            "CAINC5N-47 / CAINC5N-35 * 100",
+        )?,
+        BeaDerived::new("DERIVED-3", "Per job share", "DERIVED-1 / CAINC5N-35")?,
+    ];
+    let data = data.with_derived(&derived)?;
+    let per_job = data.filter("code", "DERIVED-1");
+    assert_eq!(per_job.len(), 2);
+    assert_eq!(per_job[0].data_value, BeaValue::Number(6250000));
+    assert!(per_job.iter().all(|v| v.unit_mult == -2));
+    assert!((per_job[0].value().unwrap() - 62500.).abs() < 1e-6);
+    assert_eq!(per_job[0].measure().to_string(), "Dollars per job");
+    assert_eq!(per_job[1].data_value, BeaValue::Suppressed);
+    let share = data.filter("code", "DERIVED-2");
+    assert_eq!(share[0].data_value, BeaValue::Number(25000000));
+    assert_eq!(share[0].unit_mult, -6);
+    assert!((share[0].value().unwrap() - 25.).abs() < 1e-9);
+    assert_eq!(share[0].measure(), BeaMeasure::Ratio);
+    assert!(
+        BeaDerived::new("DERIVED-4", "", "CAINC5N-35 + CAEMP25N-10")?
+            .evaluate(&data)
+            .is_err()
+    );
+    Ok(())
+}
+
+// Regions sum additive lines over members, recompute per capita lines and flag suppression.
+#[test]
+fn bea_regions() -> Clean<()> {
+    let datum = |code: &str, unit: &str, fips: i32, value: BeaValue| {
+        bea_datum(code, fips, 2022, unit, 0, value)
+    };
+    let dollars = "Thousands of dollars";
+    let mut records = Vec::new();
+    for (fips, income, population, mining) in [
+        (41033, 300, 100, BeaValue::Number(5)),
+        (41029, 900, 200, BeaValue::Suppressed),
+    ] {
+        records.push(datum("CAINC5N-10", dollars, fips, BeaValue::Number(income)));
+        records.push(datum(
+            "CAINC5N-20",
+            "Number of persons",
+            fips,
+            BeaValue::Number(population),
+        ));
+        records.push(datum("CAINC5N-30", "Dollars", fips, BeaValue::Number(3)));
+        records.push(datum("CAINC5N-200", dollars, fips, mining));
+        records.push(datum("CAINC5N-300", dollars, fips, BeaValue::Suppressed));
+        records.push(BeaDatum {
+            description: "Per capita personal income".to_string(),
+            ..datum("CAINC30-110", "Dollars", fips, BeaValue::Number(3))
+        });
+    }
+    let data = BeaData::from(records);
+    let regions = BeaRegions::from(vec![BeaRegion::new("Rogue Valley", 99001, &[41033, 41029])]);
+    let region = regions.aggregate(&data, RegionMissing::Suppress)?;
+    assert_eq!(region.len(), 6);
+    assert!(region.iter().all(|v| v.geo_name == "Rogue Valley"));
+    assert_eq!(region.filter("code", "CAINC5N-10")[0].value(), Some(1200.));
+    assert_eq!(region.filter("code", "CAINC5N-30")[0].value(), Some(4.));
+    assert_eq!(
+        region.filter("code", "CAINC5N-200")[0].data_value,
+        BeaValue::Suppressed
+    );
+    // Lines that cannot be summed are kept as not available rather than dropped.
+    assert_eq!(
+        region.filter("code", "CAINC30-110")[0].data_value,
+        BeaValue::NotAvailable
+    );
+    let region = regions.aggregate(&data, RegionMissing::Partial)?;
+    assert_eq!(
+        region.filter("code", "CAINC5N-200")[0].data_value,
+        BeaValue::Estimate(5)
+    );
+    // Without any member value, a partial sum stays suppressed instead of becoming zero.
+    assert_eq!(
+        region.filter("code", "CAINC5N-300")[0].data_value,
+        BeaValue::Suppressed
+    );
+    assert!(
+        BeaRegions::from(vec![BeaRegion::new("Twice", 99002, &[41033, 41033])])
+            .aggregate(&data, RegionMissing::Suppress)
+            .is_err()
+    );
+    let data = data.with_regions(&regions, RegionMissing::Suppress)?;
+    assert_eq!(data.filter("fips", "99001").len(), 6);
+    let path = std::env::temp_dir().join("bea_regions.json");
+    std::fs::write(
+        &path,
+        r#"[{"name": "Rogue Valley", "members": [41033, 41029]}, {"name": "Coast", "members": [41015]}]"#,
+    )?;
+    let regions = BeaRegions::from_json(&path)?;
+    assert_eq!(regions[1].fips, 99002);
+    std::fs::write(
+        &path,
+        r#"[{"name": "Curry", "fips": 99002, "members": [41015]}, {"name": "Coast", "members": [41015]}]"#,
+    )?;
+    assert!(BeaRegions::from_json(&path).is_err());
+    std::fs::write(&path, r#"[{"name": "Coast", "members": [41015, 41015]}]"#)?;
+    assert!(BeaRegions::from_json(&path).is_err());
+    Ok(())
+}
+
+// The FIPS catalog resolves names, state prefixes and retired codes in BEA filters.
+#[test]
+fn bea_fips_catalog() {
+    let datum = |fips: i32, name: &str| BeaDatum {
+        geo_name: name.to_string(),
+        ..bea_datum(
+            "CAINC1-1",
+            fips,
+            2022,
+            "Thousands of dollars",
+            3,
+            BeaValue::Number(1),
+        )
+    };
+    let data = BeaData::from(vec![
+        datum(0, "United States"),
+        datum(41000, "Oregon"),
+        datum(41033, "Josephine, OR"),
+        datum(41029, "Jackson, OR"),
+        datum(46113, "Shannon, SD*"),
+        datum(46102, "Oglala Lakota, SD"),
+        datum(51901, "Albemarle + Charlottesville, VA*"),
+        datum(32780, "Medford, OR (Metropolitan Statistical Area)"),
+        datum(
+            41860,
+            "San Francisco-Oakland-Berkeley, CA (Metropolitan Statistical Area)",
+        ),
+    ]);
+    let catalog = FipsCatalog::from(&data);
+    assert_eq!(catalog.get(41033).unwrap().kind, FipsKind::County);
+    assert_eq!(catalog.get(41033).unwrap().parent, Some(41000));
+    assert_eq!(catalog.get(51901).unwrap().kind, FipsKind::Combination);
+    assert_eq!(catalog.get(32780).unwrap().kind, FipsKind::Metro);
+    assert_eq!(catalog.get(98000).unwrap().kind, FipsKind::Region);
+    assert_eq!(catalog.get(46113).unwrap().name, "Shannon, SD");
+    assert_eq!(catalog.current(46113), 46102);
+    assert_eq!(catalog.find("Josephine"), vec![41033]);
+    assert_eq!(FipsCatalog::state("oregon"), Some(41000));
+    assert_eq!(catalog.get(41860).unwrap().kind, FipsKind::Metro);
+    assert_eq!(catalog.get(41860).unwrap().parent, Some(6000));
+    assert_eq!(catalog.get(32780).unwrap().parent, Some(41000));
+    // Oregon areas are in the built-in reference even without records.
+    assert_eq!(FipsCatalog::new().get(41071).unwrap().name, "Yamhill, OR");
+    assert_eq!(FipsCatalog::new().get(24420).unwrap().kind, FipsKind::Metro);
+    let path = std::env::temp_dir().join("bea_fips_reference.csv");
+    std::fs::write(
+        &path,
+        "fips,name
+6001,\"Alameda, CA\"
+41740,\"San Diego-Chula Vista-Carlsbad, CA (Metropolitan Statistical Area)\"
+",
+    )
+    .unwrap();
+    let reference = FipsCatalog::from_csv(&path).unwrap();
+    assert_eq!(reference.get(6001).unwrap().kind, FipsKind::County);
+    assert_eq!(reference.get(41740).unwrap().kind, FipsKind::Metro);
+    assert_eq!(reference.get(41740).unwrap().parent, Some(6000));
+    assert!(!reference.resolve("41*").contains(&41740));
+    assert_eq!(data.filter("fips", "41033").len(), 1);
+    assert_eq!(data.filter("fips", "00000").len(), 1);
+    assert_eq!(data.filter("fips", "46102").len(), 1);
+    assert_eq!(data.filter("fips", "Josephine").len(), 0);
+    let areas = |queries: &[&str], lineage: bool| {
+        let queries = queries.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        data.filter_areas(&catalog, &queries, lineage).len()
+    };
+    assert_eq!(areas(&["OR"], false), 3);
+    // The metro area 41860 shares the prefix but is not in Oregon.
+    assert_eq!(areas(&["41*"], false), 3);
+    assert_eq!(areas(&["Jackson, OR"], false), 1);
+    assert_eq!(areas(&["46102"], false), 1);
+    assert_eq!(areas(&["46102"], true), 2);
+    assert_eq!(areas(&["Josephine", "VA"], false), 2);
+    assert_eq!(catalog.resolve_one("Josephine").ok(), Some(41033));
+    assert!(catalog.resolve_one("OR").is_err());
+    assert!(catalog.resolve_one("Nowhere").is_err());
+}